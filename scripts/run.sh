@@ -23,7 +23,9 @@ trap 'exec 2>&4 1>&3' 0 1 2 3
 exec 1>$LOG_FILE 2>&1
 
 # Run the program
+mkdir -p /var/lib/od-badge-signup
 docker run -d -p 80:80 \
+ -v /var/lib/od-badge-signup:/data \
  --env GITHUB_ID="$GITHUB_ID" \
  --env GITHUB_SECRET="$GITHUB_SECRET" \
  --env STARKNET_ACCOUNT="$STARKNET_ACCOUNT" \
@@ -31,4 +33,5 @@ docker run -d -p 80:80 \
  --env STARKNET_BADGE_REGISTRY_ADDRESS="$STARKNET_BADGE_REGISTRY_ADDRESS" \
  --env STARKNET_CHAIN="$STARKNET_CHAIN" \
  --env ROCKET_LOG_LEVEL="$ROCKET_LOG_LEVEL" \
+ --env REGISTRATIONS_DATABASE_PATH="/data/registrations.db" \
  $IMAGE
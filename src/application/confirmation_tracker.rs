@@ -0,0 +1,695 @@
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use rocket::tokio::{
+    self,
+    sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
+};
+
+use crate::domain::{
+    entities::{Registration, RegistrationStatus},
+    services::{
+        onchain_registry::OnChainRegistry, registration_repository::RegistrationRepository,
+    },
+    value_objects::TransactionStatus,
+};
+
+pub const DEFAULT_DROPPED_AFTER_POLLS: u32 = 30;
+pub const DEFAULT_MAX_WATCH_TIME: Duration = Duration::from_secs(60 * 60);
+
+/// A submitted registration transaction, waiting to be accepted on-chain.
+pub struct PendingRegistration<R: OnChainRegistry> {
+    pub registration: Registration,
+    pub account_address: R::AccountAddress,
+    pub user_id: R::ContributorId,
+    pub transaction_hash: R::TransactionHash,
+    /// Number of transactions already sent for this registration
+    pub attempts: u32,
+    /// Number of consecutive checks the current transaction was not known by the network
+    pub not_received_polls: u32,
+    /// Start of the watch, the registration is given up on if it is not accepted in time
+    pub watched_since: SystemTime,
+}
+
+impl<R: OnChainRegistry> PendingRegistration<R> {
+    /// Pending registration of a transaction that has just been sent for the first time.
+    pub fn new(
+        registration: Registration,
+        account_address: R::AccountAddress,
+        user_id: R::ContributorId,
+        transaction_hash: R::TransactionHash,
+    ) -> Self {
+        PendingRegistration {
+            registration,
+            account_address,
+            user_id,
+            transaction_hash,
+            attempts: 1,
+            not_received_polls: 0,
+            watched_since: SystemTime::now(),
+        }
+    }
+
+    /// Rebuilds the pending registration of a `Submitted` registration record.
+    /// The number of attempts is not recorded, so the registration is considered sent once.
+    /// The watch is considered started when the registration was created, not at restart.
+    fn from_record(registration: Registration) -> Option<Self> {
+        let account_address = registration.account_address.parse().ok()?;
+        let user_id = registration.identity.clone()?.into();
+        let transaction_hash = registration.transaction_hash.as_deref()?.parse().ok()?;
+        let watched_since = registration.created_at;
+
+        Some(PendingRegistration {
+            registration,
+            account_address,
+            user_id,
+            transaction_hash,
+            attempts: 1,
+            not_received_polls: 0,
+            watched_since,
+        })
+    }
+}
+
+/// Sends registration transactions to the [ConfirmationTracker].
+pub struct ConfirmationTrackerHandle<R: OnChainRegistry> {
+    sender: UnboundedSender<PendingRegistration<R>>,
+}
+
+impl<R: OnChainRegistry> Clone for ConfirmationTrackerHandle<R> {
+    fn clone(&self) -> Self {
+        ConfirmationTrackerHandle {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<R: OnChainRegistry> ConfirmationTrackerHandle<R> {
+    pub fn track(&self, pending: PendingRegistration<R>) {
+        if self.sender.send(pending).is_err() {
+            error!(
+                "Confirmation tracker is not running, registration transaction will not be tracked"
+            );
+        }
+    }
+}
+
+pub fn channel<R: OnChainRegistry>() -> (
+    ConfirmationTrackerHandle<R>,
+    UnboundedReceiver<PendingRegistration<R>>,
+) {
+    let (sender, receiver) = unbounded_channel();
+    (ConfirmationTrackerHandle { sender }, receiver)
+}
+
+/// Background worker watching registration transactions until they are accepted,
+/// and sending them again when they get rejected or dropped.
+pub struct ConfirmationTracker<R: OnChainRegistry> {
+    registry: Arc<R>,
+    repository: Arc<dyn RegistrationRepository>,
+    receiver: UnboundedReceiver<PendingRegistration<R>>,
+    max_attempts: u32,
+    poll_interval: Duration,
+    /// Consecutive checks after which a transaction unknown to the network is considered dropped
+    dropped_after_polls: u32,
+    /// Time after which a registration that is still not accepted is given up on
+    max_watch_time: Duration,
+    /// Registrations left pending by a previous run, see `resume`
+    resumed_registrations: Vec<PendingRegistration<R>>,
+}
+
+impl<R: OnChainRegistry> ConfirmationTracker<R> {
+    pub fn new(
+        registry: Arc<R>,
+        repository: Arc<dyn RegistrationRepository>,
+        receiver: UnboundedReceiver<PendingRegistration<R>>,
+        max_attempts: u32,
+        poll_interval: Duration,
+    ) -> Self {
+        ConfirmationTracker {
+            registry,
+            repository,
+            receiver,
+            max_attempts,
+            poll_interval,
+            dropped_after_polls: DEFAULT_DROPPED_AFTER_POLLS,
+            max_watch_time: DEFAULT_MAX_WATCH_TIME,
+            resumed_registrations: Vec::new(),
+        }
+    }
+
+    /// Sends a transaction again once it has not been received by the network for `polls` checks.
+    pub fn with_dropped_after_polls(mut self, polls: u32) -> Self {
+        self.dropped_after_polls = polls;
+        self
+    }
+
+    /// Gives up on registrations that are still not accepted after `max_watch_time`.
+    pub fn with_max_watch_time(mut self, max_watch_time: Duration) -> Self {
+        self.max_watch_time = max_watch_time;
+        self
+    }
+
+    /// Loads the registrations still `Submitted` in the repository, so that their transactions
+    /// are watched again after a restart. Must be called before new registrations are tracked,
+    /// not to watch them twice.
+    pub async fn resume(&mut self) {
+        let registrations = match self
+            .repository
+            .find_by_status(RegistrationStatus::Submitted)
+            .await
+        {
+            Ok(registrations) => registrations,
+            Err(e) => {
+                error!("Failed to load submitted registrations. Error: {:?}", e);
+                return;
+            }
+        };
+
+        for registration in registrations {
+            let id = registration.id;
+            match PendingRegistration::from_record(registration) {
+                Some(pending) => self.resumed_registrations.push(pending),
+                None => warn!(
+                    "Submitted registration {} is incomplete, its transaction will not be tracked",
+                    id
+                ),
+            }
+        }
+
+        info!(
+            "resuming {} submitted registrations",
+            self.resumed_registrations.len()
+        );
+    }
+
+    /// Runs until every handle has been dropped and all pending transactions are settled.
+    pub async fn run(mut self) {
+        let mut pending_registrations = std::mem::take(&mut self.resumed_registrations);
+        let mut interval = tokio::time::interval(self.poll_interval);
+        let mut closed = false;
+
+        while !(closed && pending_registrations.is_empty()) {
+            tokio::select! {
+                pending = self.receiver.recv(), if !closed => match pending {
+                    Some(pending) => pending_registrations.push(pending),
+                    None => closed = true,
+                },
+                _ = interval.tick() => {
+                    let mut still_pending = Vec::new();
+                    for pending in pending_registrations.drain(..) {
+                        if let Some(pending) = self.check(pending).await {
+                            still_pending.push(pending);
+                        }
+                    }
+                    pending_registrations = still_pending;
+                }
+            }
+        }
+    }
+
+    /// Checks the transaction of a pending registration.
+    /// Returns the registration if it still has to be watched.
+    async fn check(&self, mut pending: PendingRegistration<R>) -> Option<PendingRegistration<R>> {
+        let status = match self
+            .registry
+            .get_transaction_status(pending.transaction_hash.clone())
+            .await
+        {
+            Ok(status) => status,
+            Err(e) => {
+                warn!(
+                    "Failed to get status of transaction {:#x}. Error: {:?}",
+                    pending.transaction_hash, e
+                );
+                if self.is_watched_for_too_long(&pending) {
+                    self.time_out(pending).await;
+                    return None;
+                }
+                return Some(pending);
+            }
+        };
+
+        match status {
+            TransactionStatus::AcceptedOnL2 | TransactionStatus::AcceptedOnL1 => {
+                let mut registration = pending.registration;
+                registration.accept();
+                self.save(&registration).await;
+                None
+            }
+            _ if self.is_watched_for_too_long(&pending) => {
+                self.time_out(pending).await;
+                None
+            }
+            TransactionStatus::Pending => {
+                pending.not_received_polls = 0;
+                Some(pending)
+            }
+            // a transaction that has just been sent may not be received by the network yet,
+            // but one that stays unknown has been dropped and will never be accepted
+            TransactionStatus::NotReceived => {
+                pending.not_received_polls += 1;
+                if pending.not_received_polls < self.dropped_after_polls {
+                    return Some(pending);
+                }
+
+                warn!(
+                    "Transaction {:#x} of registration {} has not been received after {} checks, \
+                    considering it dropped",
+                    pending.transaction_hash, pending.registration.id, pending.not_received_polls
+                );
+                self.retry(pending).await
+            }
+            TransactionStatus::Rejected { reason } => {
+                warn!(
+                    "Transaction {:#x} of registration {} has been rejected. Reason: {:?}",
+                    pending.transaction_hash, pending.registration.id, reason
+                );
+                self.retry(pending).await
+            }
+        }
+    }
+
+    fn is_watched_for_too_long(&self, pending: &PendingRegistration<R>) -> bool {
+        matches!(
+            pending.watched_since.elapsed(),
+            Ok(watch_time) if watch_time >= self.max_watch_time
+        )
+    }
+
+    async fn time_out(&self, mut pending: PendingRegistration<R>) {
+        error!(
+            "Registration {} has not been accepted after {:?}, giving up",
+            pending.registration.id, self.max_watch_time
+        );
+        pending.registration.time_out();
+        self.save(&pending.registration).await;
+    }
+
+    async fn retry(&self, mut pending: PendingRegistration<R>) -> Option<PendingRegistration<R>> {
+        if pending.attempts >= self.max_attempts {
+            error!(
+                "Registration {} has been sent {} times without being accepted, giving up",
+                pending.registration.id, pending.attempts
+            );
+            pending.registration.reject();
+            self.save(&pending.registration).await;
+            return None;
+        }
+
+        pending.attempts += 1;
+
+        // a new transaction is sent with a fresh nonce
+        match self
+            .registry
+            .register_contributor(pending.account_address.clone(), pending.user_id.clone())
+            .await
+        {
+            Ok(transaction_hash) => {
+                pending
+                    .registration
+                    .submit(format!("{:#x}", transaction_hash));
+                self.save(&pending.registration).await;
+                pending.transaction_hash = transaction_hash;
+                pending.not_received_polls = 0;
+            }
+            // the rejected transaction is kept, so that a new attempt is made on the next check
+            Err(e) => error!(
+                "Failed to send again registration {}. Error: {:?}",
+                pending.registration.id, e
+            ),
+        }
+
+        Some(pending)
+    }
+
+    async fn save(&self, registration: &Registration) {
+        if let Err(e) = self.repository.save(registration).await {
+            error!(
+                "Failed to save registration {}. Error: {:?}",
+                registration.id, e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::Arc,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    };
+
+    use claim::{assert_none, assert_some};
+    use mockall::{mock, predicate::eq};
+    use rocket::tokio;
+    use starknet::{core::types::FieldElement, macros::felt};
+
+    use super::{channel, ConfirmationTracker, PendingRegistration};
+    use crate::{
+        domain::{
+            entities::{Registration, RegistrationStatus},
+            errors::{RegistryError, RepositoryError, SignatureError},
+            services::{
+                onchain_registry::OnChainRegistry, registration_repository::RegistrationRepository,
+            },
+            value_objects::{Identity, Nonce, SignedOperation, TransactionStatus},
+        },
+        infrastructure::StarknetSignedData,
+    };
+
+    mock! {
+        MyOnChainRegistry {}
+        #[async_trait]
+        impl OnChainRegistry for MyOnChainRegistry {
+            type SignedData = StarknetSignedData;
+            type AccountAddress = FieldElement;
+            type TransactionHash = FieldElement;
+            type ContributorId = FieldElement;
+
+            async fn check_signature(
+                &self,
+                signed_data: <MockMyOnChainRegistry as OnChainRegistry>::SignedData,
+                account_address: <MockMyOnChainRegistry as OnChainRegistry>::AccountAddress,
+                user_id: <MockMyOnChainRegistry as OnChainRegistry>::ContributorId,
+                nonce: Nonce,
+                operation: SignedOperation,
+            ) -> Result<(), SignatureError>;
+
+            async fn check_migration_signature(
+                &self,
+                signed_data: <MockMyOnChainRegistry as OnChainRegistry>::SignedData,
+                signer_address: <MockMyOnChainRegistry as OnChainRegistry>::AccountAddress,
+                old_account_address: <MockMyOnChainRegistry as OnChainRegistry>::AccountAddress,
+                new_account_address: <MockMyOnChainRegistry as OnChainRegistry>::AccountAddress,
+                user_id: <MockMyOnChainRegistry as OnChainRegistry>::ContributorId,
+                nonce: Nonce,
+            ) -> Result<(), SignatureError>;
+
+            async fn get_registered_account(
+                &self,
+                user_id: <MockMyOnChainRegistry as OnChainRegistry>::ContributorId,
+            ) -> Result<Option<<MockMyOnChainRegistry as OnChainRegistry>::AccountAddress>, RegistryError>;
+
+            async fn register_contributor(
+                &self,
+                user_account_address: <MockMyOnChainRegistry as OnChainRegistry>::AccountAddress,
+                user_id: <MockMyOnChainRegistry as OnChainRegistry>::ContributorId,
+            ) -> Result<<MockMyOnChainRegistry as OnChainRegistry>::TransactionHash, RegistryError>;
+
+            async fn unregister_contributor(
+                &self,
+                user_account_address: <MockMyOnChainRegistry as OnChainRegistry>::AccountAddress,
+                user_id: <MockMyOnChainRegistry as OnChainRegistry>::ContributorId,
+            ) -> Result<<MockMyOnChainRegistry as OnChainRegistry>::TransactionHash, RegistryError>;
+
+            async fn migrate_contributor(
+                &self,
+                old_account_address: <MockMyOnChainRegistry as OnChainRegistry>::AccountAddress,
+                new_account_address: <MockMyOnChainRegistry as OnChainRegistry>::AccountAddress,
+                user_id: <MockMyOnChainRegistry as OnChainRegistry>::ContributorId,
+            ) -> Result<<MockMyOnChainRegistry as OnChainRegistry>::TransactionHash, RegistryError>;
+
+            async fn get_transaction_status(
+                &self,
+                transaction_hash: <MockMyOnChainRegistry as OnChainRegistry>::TransactionHash,
+            ) -> Result<TransactionStatus, RegistryError>;
+        }
+    }
+
+    mock! {
+        MyRegistrationRepository {}
+        #[async_trait]
+        impl RegistrationRepository for MyRegistrationRepository {
+            async fn create(&self, account_address: String) -> Result<Registration, RepositoryError>;
+
+            async fn save(&self, registration: &Registration) -> Result<(), RepositoryError>;
+
+            async fn find_by_identity(
+                &self,
+                identity: &Identity,
+            ) -> Result<Vec<Registration>, RepositoryError>;
+
+            async fn find_by_transaction_hash(
+                &self,
+                transaction_hash: &str,
+            ) -> Result<Option<Registration>, RepositoryError>;
+
+            async fn find_by_status(
+                &self,
+                status: RegistrationStatus,
+            ) -> Result<Vec<Registration>, RepositoryError>;
+        }
+    }
+
+    fn new_tracker(
+        registry_mock: MockMyOnChainRegistry,
+        repository_mock: MockMyRegistrationRepository,
+    ) -> ConfirmationTracker<MockMyOnChainRegistry> {
+        let (_, receiver) = channel();
+        ConfirmationTracker::new(
+            Arc::new(registry_mock),
+            Arc::new(repository_mock),
+            receiver,
+            2,
+            Duration::from_secs(1),
+        )
+        .with_dropped_after_polls(3)
+        .with_max_watch_time(Duration::from_secs(60))
+    }
+
+    fn new_pending_registration(attempts: u32) -> PendingRegistration<MockMyOnChainRegistry> {
+        PendingRegistration {
+            registration: Registration {
+                id: 1,
+                status: RegistrationStatus::Submitted,
+                identity: Some(Identity::GitHubId(42.into())),
+                profile: None,
+                account_address: "0x65f1".to_string(),
+                transaction_hash: Some("0x666".to_string()),
+                error_kind: None,
+                created_at: UNIX_EPOCH,
+                updated_at: UNIX_EPOCH,
+            },
+            account_address: felt!("0x65f1"),
+            user_id: FieldElement::from(42u32),
+            transaction_hash: felt!("0x666"),
+            attempts,
+            not_received_polls: 0,
+            watched_since: SystemTime::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn submitted_registrations_are_resumed() {
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_find_by_status()
+            .with(eq(RegistrationStatus::Submitted))
+            .times(1)
+            .returning(|_| {
+                let incomplete = Registration {
+                    id: 2,
+                    identity: None,
+                    ..new_pending_registration(1).registration
+                };
+                Ok(vec![new_pending_registration(1).registration, incomplete])
+            });
+
+        let mut tracker = new_tracker(MockMyOnChainRegistry::new(), repository_mock);
+        tracker.resume().await;
+
+        assert_eq!(tracker.resumed_registrations.len(), 1);
+        let pending = &tracker.resumed_registrations[0];
+        assert_eq!(pending.registration.id, 1);
+        assert_eq!(pending.account_address, felt!("0x65f1"));
+        assert_eq!(
+            pending.user_id,
+            FieldElement::from(Identity::GitHubId(42.into()))
+        );
+        assert_eq!(pending.transaction_hash, felt!("0x666"));
+    }
+
+    #[tokio::test]
+    async fn pending_transaction_is_still_watched() {
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_get_transaction_status()
+            .with(eq(felt!("0x666")))
+            .times(1)
+            .returning(|_| Ok(TransactionStatus::Pending));
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock.expect_save().never();
+
+        let tracker = new_tracker(registry_mock, repository_mock);
+
+        assert_some!(tracker.check(new_pending_registration(1)).await);
+    }
+
+    #[tokio::test]
+    async fn accepted_transaction_is_recorded() {
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_get_transaction_status()
+            .with(eq(felt!("0x666")))
+            .times(1)
+            .returning(|_| Ok(TransactionStatus::AcceptedOnL2));
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_save()
+            .withf(|registration| registration.status == RegistrationStatus::Accepted)
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let tracker = new_tracker(registry_mock, repository_mock);
+
+        assert_none!(tracker.check(new_pending_registration(1)).await);
+    }
+
+    #[tokio::test]
+    async fn rejected_transaction_is_sent_again() {
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_get_transaction_status()
+            .with(eq(felt!("0x666")))
+            .times(1)
+            .returning(|_| Ok(TransactionStatus::Rejected { reason: None }));
+        registry_mock
+            .expect_register_contributor()
+            .with(eq(felt!("0x65f1")), eq(FieldElement::from(42u32)))
+            .times(1)
+            .returning(|_, _| Ok(felt!("0x777")));
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_save()
+            .withf(|registration| {
+                registration.status == RegistrationStatus::Submitted
+                    && registration.transaction_hash == Some("0x777".to_string())
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let tracker = new_tracker(registry_mock, repository_mock);
+
+        let pending = tracker.check(new_pending_registration(1)).await.unwrap();
+        assert_eq!(pending.transaction_hash, felt!("0x777"));
+        assert_eq!(pending.attempts, 2);
+    }
+
+    #[tokio::test]
+    async fn rejected_transaction_is_abandoned_after_max_attempts() {
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_get_transaction_status()
+            .with(eq(felt!("0x666")))
+            .times(1)
+            .returning(|_| Ok(TransactionStatus::Rejected { reason: None }));
+        registry_mock.expect_register_contributor().never();
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_save()
+            .withf(|registration| registration.status == RegistrationStatus::Rejected)
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let tracker = new_tracker(registry_mock, repository_mock);
+
+        assert_none!(tracker.check(new_pending_registration(2)).await);
+    }
+
+    #[tokio::test]
+    async fn not_received_transaction_is_still_watched() {
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_get_transaction_status()
+            .with(eq(felt!("0x666")))
+            .times(1)
+            .returning(|_| Ok(TransactionStatus::NotReceived));
+        registry_mock.expect_register_contributor().never();
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock.expect_save().never();
+
+        let tracker = new_tracker(registry_mock, repository_mock);
+
+        let pending = tracker.check(new_pending_registration(1)).await.unwrap();
+        assert_eq!(pending.not_received_polls, 1);
+        assert_eq!(pending.transaction_hash, felt!("0x666"));
+    }
+
+    #[tokio::test]
+    async fn dropped_transaction_is_sent_again() {
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_get_transaction_status()
+            .with(eq(felt!("0x666")))
+            .times(1)
+            .returning(|_| Ok(TransactionStatus::NotReceived));
+        registry_mock
+            .expect_register_contributor()
+            .with(eq(felt!("0x65f1")), eq(FieldElement::from(42u32)))
+            .times(1)
+            .returning(|_, _| Ok(felt!("0x777")));
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_save()
+            .withf(|registration| {
+                registration.status == RegistrationStatus::Submitted
+                    && registration.transaction_hash == Some("0x777".to_string())
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let tracker = new_tracker(registry_mock, repository_mock);
+
+        let pending = tracker
+            .check(PendingRegistration {
+                not_received_polls: 2,
+                ..new_pending_registration(1)
+            })
+            .await
+            .unwrap();
+        assert_eq!(pending.transaction_hash, felt!("0x777"));
+        assert_eq!(pending.attempts, 2);
+        assert_eq!(pending.not_received_polls, 0);
+    }
+
+    #[tokio::test]
+    async fn registration_is_given_up_after_max_watch_time() {
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_get_transaction_status()
+            .with(eq(felt!("0x666")))
+            .times(1)
+            .returning(|_| Ok(TransactionStatus::Pending));
+        registry_mock.expect_register_contributor().never();
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_save()
+            .withf(|registration| {
+                registration.status == RegistrationStatus::Rejected
+                    && registration.error_kind == Some("transaction_timed_out".to_string())
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let tracker = new_tracker(registry_mock, repository_mock);
+
+        assert_none!(
+            tracker
+                .check(PendingRegistration {
+                    watched_since: UNIX_EPOCH,
+                    ..new_pending_registration(1)
+                })
+                .await
+        );
+    }
+}
@@ -1 +1,2 @@
+pub mod confirmation_tracker;
 pub mod registerer;
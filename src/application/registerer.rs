@@ -1,6 +1,21 @@
-use crate::domain::{
-    errors::RegistrationError,
-    services::{identity_provider::IdentityProvider, onchain_registry::OnChainRegistry},
+use std::sync::Arc;
+
+use crate::{
+    application::confirmation_tracker::{ConfirmationTrackerHandle, PendingRegistration},
+    domain::{
+        entities::{Registration, RegistrationStatus},
+        errors::{AuthorizationStateError, IdentitySessionError, RegistrationError},
+        services::{
+            authorization_state_store::AuthorizationStateStore,
+            eligibility_policy::EligibilityPolicy, identity_provider::IdentityProvider,
+            identity_session_store::IdentitySessionStore, nonce_store::NonceStore,
+            onchain_registry::OnChainRegistry, registration_repository::RegistrationRepository,
+        },
+        value_objects::{
+            AccessToken, Authorization, Challenge, Identity, IdentityProfile, IdentitySession,
+            Nonce, RegistrationOutcome, SignedOperation, TransactionStatus,
+        },
+    },
 };
 
 #[async_trait]
@@ -9,12 +24,54 @@ where
     P: IdentityProvider,
     R: OnChainRegistry,
 {
+    /// Issues a one-time challenge nonce, to be signed by `account_address` only.
+    async fn new_challenge(&self, account_address: R::AccountAddress) -> Challenge;
+
+    /// Issues an OAuth authorization request, and returns the URL of the authorization page the
+    /// user must be redirected to. None if authorization requests are not issued for the provider.
+    async fn new_authorization_url(&self) -> Option<String>;
+
+    /// Identifies the user, and opens a short-lived session which stands for the authorization
+    /// when registering once the account has signed. Eligibility is checked right away.
+    async fn open_session(
+        &self,
+        authorization: Authorization,
+    ) -> Result<IdentitySession, RegistrationError>;
+
+    /// Registers the user with `account_address`, and returns the outcome along with the user profile.
     async fn register_contributor(
         &self,
-        authorization_code: String,
+        authorization: Authorization,
+        account_address: R::AccountAddress,
+        signed_data: R::SignedData,
+        nonce: Nonce,
+    ) -> Result<(RegistrationOutcome<R::TransactionHash>, IdentityProfile), RegistrationError>;
+
+    /// Unbinds the identity of the user from `account_address`, proven by `signed_data`.
+    async fn unregister_contributor(
+        &self,
+        authorization: Authorization,
         account_address: R::AccountAddress,
         signed_data: R::SignedData,
+        nonce: Nonce,
+    ) -> Result<R::TransactionHash, RegistrationError>;
+
+    /// Moves the registration of the user from `old_account_address` to `new_account_address`.
+    /// The new account must sign the migration message, the old one can optionally sign it too.
+    async fn migrate_contributor(
+        &self,
+        authorization: Authorization,
+        old_account_address: R::AccountAddress,
+        new_account_address: R::AccountAddress,
+        new_signed_data: R::SignedData,
+        old_signed_data: Option<R::SignedData>,
+        nonce: Nonce,
     ) -> Result<R::TransactionHash, RegistrationError>;
+
+    async fn get_registration_status(
+        &self,
+        transaction_hash: R::TransactionHash,
+    ) -> Result<TransactionStatus, RegistrationError>;
 }
 
 pub struct RegistererImpl<P, R>
@@ -23,7 +80,18 @@ where
     R: OnChainRegistry,
 {
     identity_provider: P,
-    registry: R,
+    registry: Arc<R>,
+    nonce_store: Arc<dyn NonceStore>,
+    repository: Arc<dyn RegistrationRepository>,
+    confirmation_tracker: ConfirmationTrackerHandle<R>,
+    eligibility_policy: Box<dyn EligibilityPolicy>,
+    /// Organizations or teams the user must belong to, to register. Anybody can register if empty.
+    required_memberships: Vec<String>,
+    /// Issued OAuth states. When set, authorization codes must come with one of them.
+    authorization_states: Option<Arc<dyn AuthorizationStateStore>>,
+    /// Whether authorization codes sent without a state are accepted anyway
+    codes_without_state_allowed: bool,
+    identity_sessions: Option<Arc<dyn IdentitySessionStore>>,
 }
 
 impl<P, R> RegistererImpl<P, R>
@@ -31,10 +99,351 @@ where
     P: IdentityProvider,
     R: OnChainRegistry,
 {
-    pub fn new(identity_provider: P, registry: R) -> Self {
+    pub fn new(
+        identity_provider: P,
+        registry: Arc<R>,
+        nonce_store: Arc<dyn NonceStore>,
+        repository: Arc<dyn RegistrationRepository>,
+        confirmation_tracker: ConfirmationTrackerHandle<R>,
+        eligibility_policy: Box<dyn EligibilityPolicy>,
+    ) -> Self {
         RegistererImpl::<P, R> {
             identity_provider,
             registry,
+            nonce_store,
+            repository,
+            confirmation_tracker,
+            eligibility_policy,
+            required_memberships: Vec::new(),
+            authorization_states: None,
+            codes_without_state_allowed: false,
+            identity_sessions: None,
+        }
+    }
+
+    /// Restricts registrations to members of one of the `memberships` organizations or teams.
+    pub fn with_required_memberships(mut self, memberships: Vec<String>) -> Self {
+        self.required_memberships = memberships;
+        self
+    }
+
+    /// Issues authorization requests, and only accepts authorization codes sent with the OAuth
+    /// state of one of them. Their PKCE code verifier is sent along with the code.
+    /// Codes sent without a state are rejected, unless `with_codes_without_state` is set.
+    pub fn with_authorization_states(
+        mut self,
+        authorization_states: Arc<dyn AuthorizationStateStore>,
+    ) -> Self {
+        self.authorization_states = Some(authorization_states);
+        self
+    }
+
+    /// Also accepts authorization codes sent without a state, e.g. by frontends sending users to
+    /// the authorization page themselves. Such codes are not bound to an authorization request
+    /// issued by `new_authorization_url`, so nothing protects them against login CSRF.
+    pub fn with_codes_without_state(mut self) -> Self {
+        self.codes_without_state_allowed = true;
+        self
+    }
+
+    /// Lets users be identified before they sign with their account, see `open_session`.
+    pub fn with_identity_sessions(
+        mut self,
+        identity_sessions: Arc<dyn IdentitySessionStore>,
+    ) -> Self {
+        self.identity_sessions = Some(identity_sessions);
+        self
+    }
+
+    fn identity_sessions(&self) -> Result<&dyn IdentitySessionStore, RegistrationError> {
+        self.identity_sessions
+            .as_deref()
+            .ok_or(RegistrationError::Session(
+                IdentitySessionError::Unsupported,
+            ))
+    }
+
+    /// Consumes the OAuth `state` of the authorization request, and returns its code verifier.
+    /// States are not checked if authorization requests are not issued.
+    async fn consume_state(
+        &self,
+        state: Option<&str>,
+    ) -> Result<Option<String>, RegistrationError> {
+        let authorization_states = match &self.authorization_states {
+            Some(authorization_states) => authorization_states,
+            None => return Ok(None),
+        };
+
+        let state = match state {
+            Some(state) => state,
+            // codes obtained without an authorization request of the server
+            None if self.codes_without_state_allowed => return Ok(None),
+            None => return Err(RegistrationError::State(AuthorizationStateError::Missing)),
+        };
+        let authorization_state = authorization_states
+            .consume(state)
+            .await
+            .map_err(RegistrationError::State)?;
+
+        Ok(Some(authorization_state.code_verifier))
+    }
+
+    /// Consumes the challenge `nonce` issued for `account_address`. It must only be called once
+    /// the user is identified and the signature checked, so that nobody else can use up the nonce.
+    async fn consume_nonce(
+        &self,
+        nonce: &Nonce,
+        account_address: &R::AccountAddress,
+    ) -> Result<(), RegistrationError> {
+        self.nonce_store
+            .consume(nonce, &format!("{:#x}", account_address))
+            .await
+            .map_err(RegistrationError::Challenge)
+    }
+
+    /// Closes the identity session the user was identified with, if any. Like nonces, sessions
+    /// are only used up once the signature is checked, so that the user can sign again.
+    async fn close_session(&self, authorization: &Authorization) -> Result<(), RegistrationError> {
+        if let Authorization::Session(id) = authorization {
+            self.identity_sessions()?
+                .close(id)
+                .await
+                .map_err(RegistrationError::Session)?;
+        }
+
+        Ok(())
+    }
+
+    /// Identifies the user with the identity provider, or with a previously opened session.
+    /// Access tokens obtained from an authorization code are revoked as soon as the user is
+    /// identified, whatever the outcome. Access tokens sent by the user are left to them.
+    async fn identify_user(
+        &self,
+        authorization: &Authorization,
+        operation: SignedOperation,
+    ) -> Result<(Identity, IdentityProfile), RegistrationError> {
+        let (authorization_code, state) = match authorization {
+            Authorization::Code { code, state } => (code, state),
+            Authorization::AccessToken(access_token) => {
+                return self.get_user(access_token, operation).await;
+            }
+            // the session is only closed once the signature is checked, see `close_session`
+            Authorization::Session(id) => {
+                let session = self
+                    .identity_sessions()?
+                    .get(id)
+                    .await
+                    .map_err(RegistrationError::Session)?;
+                return Ok((session.identity, session.profile));
+            }
+        };
+        let code_verifier = self.consume_state(state.as_deref()).await?;
+
+        let access_token = self
+            .identity_provider
+            .new_access_token(authorization_code, code_verifier)
+            .await
+            .map_err(RegistrationError::Authentication)?;
+
+        let result = self.get_user(&access_token, operation).await;
+
+        if let Err(e) = self
+            .identity_provider
+            .revoke_access_token(&access_token)
+            .await
+        {
+            error!("Failed to revoke access token. Error: {:?}", e);
+        }
+
+        result
+    }
+
+    async fn get_user(
+        &self,
+        access_token: &AccessToken,
+        operation: SignedOperation,
+    ) -> Result<(Identity, IdentityProfile), RegistrationError> {
+        let user = self
+            .identity_provider
+            .get_user(access_token)
+            .await
+            .map_err(RegistrationError::Identification)?;
+
+        // memberships are only required to register
+        if operation == SignedOperation::Registration {
+            self.check_memberships(access_token).await?;
+        }
+
+        Ok(user)
+    }
+
+    async fn check_memberships(&self, access_token: &AccessToken) -> Result<(), RegistrationError> {
+        if self.required_memberships.is_empty() {
+            return Ok(());
+        }
+
+        let memberships = self
+            .identity_provider
+            .get_memberships(access_token)
+            .await
+            .map_err(RegistrationError::Identification)?;
+
+        let is_member = memberships.iter().any(|membership| {
+            self.required_memberships
+                .iter()
+                .any(|required| required.eq_ignore_ascii_case(membership))
+        });
+
+        if is_member {
+            Ok(())
+        } else {
+            Err(RegistrationError::NotAMember)
+        }
+    }
+
+    async fn try_register_contributor(
+        &self,
+        registration: &mut Registration,
+        authorization: Authorization,
+        account_address: R::AccountAddress,
+        signed_data: R::SignedData,
+        nonce: Nonce,
+    ) -> Result<
+        (
+            RegistrationOutcome<R::TransactionHash>,
+            R::ContributorId,
+            IdentityProfile,
+        ),
+        RegistrationError,
+    > {
+        let (identity, profile) = self
+            .identify_user(&authorization, SignedOperation::Registration)
+            .await?;
+
+        registration.identity = Some(identity.clone());
+        registration.profile = Some(profile.clone());
+
+        // no gas must be spent on accounts that are not eligible
+        self.eligibility_policy.check(&identity, &profile)?;
+        let user_id: R::ContributorId = identity.clone().into();
+
+        self.registry
+            .check_signature(
+                signed_data,
+                account_address.clone(),
+                user_id.clone(),
+                nonce,
+                SignedOperation::Registration,
+            )
+            .await
+            .map_err(RegistrationError::Signature)?;
+        self.consume_nonce(&nonce, &account_address).await?;
+        self.close_session(&authorization).await?;
+
+        let registered_account = self
+            .registry
+            .get_registered_account(user_id.clone())
+            .await
+            .map_err(RegistrationError::Registry)?;
+
+        match registered_account {
+            Some(registered_account) if registered_account == account_address => {
+                let transaction_hash = self
+                    .find_transaction_hash(&identity, &registration.account_address)
+                    .await;
+                return Ok((
+                    RegistrationOutcome::AlreadyRegistered { transaction_hash },
+                    user_id,
+                    profile,
+                ));
+            }
+            Some(_) => return Err(RegistrationError::AlreadyRegistered),
+            None => (),
+        }
+
+        // the transaction of a previous request may not be accepted yet, it must not be sent twice
+        if let Some(submitted) = self.find_submitted_registration(&identity).await? {
+            if submitted.account_address != registration.account_address {
+                return Err(RegistrationError::AlreadyRegistered);
+            }
+            return Ok((
+                RegistrationOutcome::AlreadyRegistered {
+                    transaction_hash: submitted
+                        .transaction_hash
+                        .and_then(|transaction_hash| transaction_hash.parse().ok()),
+                },
+                user_id,
+                profile,
+            ));
+        }
+
+        let transaction_hash = self
+            .registry
+            .register_contributor(account_address, user_id.clone())
+            .await
+            .map_err(RegistrationError::Registry)?;
+
+        Ok((
+            RegistrationOutcome::Submitted(transaction_hash),
+            user_id,
+            profile,
+        ))
+    }
+
+    /// Last registration of `identity` whose transaction has been sent and is not settled yet.
+    async fn find_submitted_registration(
+        &self,
+        identity: &Identity,
+    ) -> Result<Option<Registration>, RegistrationError> {
+        let registrations = self
+            .repository
+            .find_by_identity(identity)
+            .await
+            .map_err(RegistrationError::Repository)?;
+
+        Ok(registrations
+            .into_iter()
+            .rev()
+            .find(|registration| registration.status == RegistrationStatus::Submitted))
+    }
+
+    /// Hash of the last transaction sent by this service to register `identity` with `account_address`.
+    async fn find_transaction_hash(
+        &self,
+        identity: &Identity,
+        account_address: &str,
+    ) -> Option<R::TransactionHash> {
+        let registrations = match self.repository.find_by_identity(identity).await {
+            Ok(registrations) => registrations,
+            Err(e) => {
+                warn!(
+                    "Failed to find previous registrations of {:?}. Error: {:?}",
+                    identity, e
+                );
+                return None;
+            }
+        };
+
+        registrations
+            .into_iter()
+            .rev()
+            .filter(|registration| {
+                registration.account_address == account_address
+                    && matches!(
+                        registration.status,
+                        RegistrationStatus::Submitted | RegistrationStatus::Accepted
+                    )
+            })
+            .find_map(|registration| registration.transaction_hash?.parse().ok())
+    }
+
+    async fn save(&self, registration: &Registration) {
+        // the registration outcome must reach the user even if it cannot be recorded
+        if let Err(e) = self.repository.save(registration).await {
+            error!(
+                "Failed to save registration {}. Error: {:?}",
+                registration.id, e
+            );
         }
     }
 }
@@ -45,54 +454,249 @@ where
     P: IdentityProvider,
     R: OnChainRegistry,
 {
+    async fn new_challenge(&self, account_address: R::AccountAddress) -> Challenge {
+        self.nonce_store
+            .issue(&format!("{:#x}", account_address))
+            .await
+    }
+
+    async fn new_authorization_url(&self) -> Option<String> {
+        let authorization_state = self.authorization_states.as_ref()?.issue().await;
+        self.identity_provider.authorization_url(
+            &authorization_state.state,
+            &authorization_state.code_challenge(),
+        )
+    }
+
+    async fn open_session(
+        &self,
+        authorization: Authorization,
+    ) -> Result<IdentitySession, RegistrationError> {
+        let identity_sessions = self.identity_sessions()?;
+
+        let (identity, profile) = self
+            .identify_user(&authorization, SignedOperation::Registration)
+            .await?;
+        self.eligibility_policy.check(&identity, &profile)?;
+
+        Ok(identity_sessions.open(identity, profile).await)
+    }
+
     async fn register_contributor(
         &self,
-        authorization_code: String,
+        authorization: Authorization,
+        account_address: R::AccountAddress,
+        signed_data: R::SignedData,
+        nonce: Nonce,
+    ) -> Result<(RegistrationOutcome<R::TransactionHash>, IdentityProfile), RegistrationError> {
+        let mut registration = self
+            .repository
+            .create(format!("{:#x}", account_address))
+            .await
+            .map_err(RegistrationError::Repository)?;
+
+        let result = self
+            .try_register_contributor(
+                &mut registration,
+                authorization,
+                account_address.clone(),
+                signed_data,
+                nonce,
+            )
+            .await;
+
+        match result {
+            Ok((RegistrationOutcome::Submitted(transaction_hash), user_id, profile)) => {
+                registration.submit(format!("{:#x}", transaction_hash));
+                self.save(&registration).await;
+                self.confirmation_tracker.track(PendingRegistration::new(
+                    registration,
+                    account_address,
+                    user_id,
+                    transaction_hash.clone(),
+                ));
+                Ok((RegistrationOutcome::Submitted(transaction_hash), profile))
+            }
+            Ok((RegistrationOutcome::AlreadyRegistered { transaction_hash }, _, profile)) => {
+                registration.already_registered(
+                    transaction_hash
+                        .as_ref()
+                        .map(|transaction_hash| format!("{:#x}", transaction_hash)),
+                );
+                self.save(&registration).await;
+                Ok((
+                    RegistrationOutcome::AlreadyRegistered { transaction_hash },
+                    profile,
+                ))
+            }
+            Err(e) => {
+                registration.fail(e.kind());
+                self.save(&registration).await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn unregister_contributor(
+        &self,
+        authorization: Authorization,
         account_address: R::AccountAddress,
         signed_data: R::SignedData,
+        nonce: Nonce,
     ) -> Result<R::TransactionHash, RegistrationError> {
-        let access_token = self
-            .identity_provider
-            .new_access_token(&authorization_code)
+        let (identity, _) = self
+            .identify_user(&authorization, SignedOperation::Unregistration)
+            .await?;
+        let user_id: R::ContributorId = identity.into();
+
+        self.registry
+            .check_signature(
+                signed_data,
+                account_address.clone(),
+                user_id.clone(),
+                nonce,
+                SignedOperation::Unregistration,
+            )
             .await
-            .map_err(RegistrationError::Authentication)?;
+            .map_err(RegistrationError::Signature)?;
+        self.consume_nonce(&nonce, &account_address).await?;
+        self.close_session(&authorization).await?;
 
-        let user_id = self
-            .identity_provider
-            .get_user_id(&access_token)
+        let registered_account = self
+            .registry
+            .get_registered_account(user_id.clone())
             .await
-            .map_err(RegistrationError::Identification)?;
+            .map_err(RegistrationError::Registry)?;
 
+        if registered_account.as_ref() != Some(&account_address) {
+            return Err(RegistrationError::NotRegistered);
+        }
+
+        self.registry
+            .unregister_contributor(account_address, user_id)
+            .await
+            .map_err(RegistrationError::Registry)
+    }
+
+    async fn migrate_contributor(
+        &self,
+        authorization: Authorization,
+        old_account_address: R::AccountAddress,
+        new_account_address: R::AccountAddress,
+        new_signed_data: R::SignedData,
+        old_signed_data: Option<R::SignedData>,
+        nonce: Nonce,
+    ) -> Result<R::TransactionHash, RegistrationError> {
+        let (identity, _) = self
+            .identify_user(&authorization, SignedOperation::Migration)
+            .await?;
+        let user_id: R::ContributorId = identity.into();
+
+        // both signatures cover the same message, binding the old and the new accounts together
         self.registry
-            .check_signature(signed_data, account_address.clone())
+            .check_migration_signature(
+                new_signed_data,
+                new_account_address.clone(),
+                old_account_address.clone(),
+                new_account_address.clone(),
+                user_id.clone(),
+                nonce,
+            )
             .await
             .map_err(RegistrationError::Signature)?;
 
-        let transaction_hash = self
+        if let Some(old_signed_data) = old_signed_data {
+            self.registry
+                .check_migration_signature(
+                    old_signed_data,
+                    old_account_address.clone(),
+                    old_account_address.clone(),
+                    new_account_address.clone(),
+                    user_id.clone(),
+                    nonce,
+                )
+                .await
+                .map_err(RegistrationError::Signature)?;
+        }
+        self.consume_nonce(&nonce, &new_account_address).await?;
+        self.close_session(&authorization).await?;
+
+        let registered_account = self
+            .registry
+            .get_registered_account(user_id.clone())
+            .await
+            .map_err(RegistrationError::Registry)?;
+
+        if registered_account.as_ref() != Some(&old_account_address) {
+            return Err(RegistrationError::NotRegistered);
+        }
+
+        self.registry
+            .migrate_contributor(old_account_address, new_account_address, user_id)
+            .await
+            .map_err(RegistrationError::Registry)
+    }
+
+    async fn get_registration_status(
+        &self,
+        transaction_hash: R::TransactionHash,
+    ) -> Result<TransactionStatus, RegistrationError> {
+        let status = self
             .registry
-            .register_contributor(account_address, user_id.into())
+            .get_transaction_status(transaction_hash.clone())
             .await
             .map_err(RegistrationError::Registry)?;
+        if status != TransactionStatus::NotReceived {
+            return Ok(status);
+        }
 
-        Ok(transaction_hash)
+        // transactions sent by the service may not be received by the network yet
+        self.repository
+            .find_by_transaction_hash(&format!("{:#x}", transaction_hash))
+            .await
+            .map_err(RegistrationError::Repository)?
+            .map(|_| status)
+            .ok_or(RegistrationError::UnknownTransaction)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::{sync::Arc, time::UNIX_EPOCH};
+
     use claim::assert_ok_eq;
-    use mockall::{mock, predicate::eq};
+    use mockall::{
+        mock,
+        predicate::{always, eq},
+    };
     use rocket::tokio;
     use starknet::{core::types::FieldElement, macros::felt};
 
     use crate::infrastructure::StarknetSignature;
     use crate::infrastructure::StarknetSignedData;
     use crate::{
-        application::registerer::{Registerer, RegistererImpl},
+        application::{
+            confirmation_tracker,
+            registerer::{Registerer, RegistererImpl},
+        },
         domain::{
-            errors::{AuthenticationError, IdentificationError, RegistryError, SignatureError},
-            services::{identity_provider::IdentityProvider, onchain_registry::OnChainRegistry},
-            value_objects::{AccessToken, Identity},
+            entities::{Registration, RegistrationStatus},
+            errors::{
+                AuthenticationError, AuthorizationStateError, ChallengeError, EligibilityError,
+                IdentificationError, IdentitySessionError, RegistrationError, RegistryError,
+                RepositoryError, SignatureError,
+            },
+            services::{
+                authorization_state_store::AuthorizationStateStore,
+                eligibility_policy::EligibilityPolicy, identity_provider::IdentityProvider,
+                identity_session_store::IdentitySessionStore, nonce_store::NonceStore,
+                onchain_registry::OnChainRegistry, registration_repository::RegistrationRepository,
+            },
+            value_objects::{
+                AccessToken, Authorization, AuthorizationState, Challenge, Identity,
+                IdentityProfile, IdentitySession, Nonce, RegistrationOutcome, SignedOperation,
+                TransactionStatus,
+            },
         },
     };
 
@@ -103,12 +707,25 @@ mod tests {
             async fn new_access_token(
                 &self,
                 authorization_code: &str,
+                code_verifier: Option<String>,
             ) -> Result<AccessToken, AuthenticationError>;
 
-            async fn get_user_id(
+            async fn get_user(
+                &self,
+                access_token: &AccessToken,
+            ) -> Result<(Identity, IdentityProfile), IdentificationError>;
+
+            fn authorization_url(&self, state: &str, code_challenge: &str) -> Option<String>;
+
+            async fn get_memberships(
+                &self,
+                access_token: &AccessToken,
+            ) -> Result<Vec<String>, IdentificationError>;
+
+            async fn revoke_access_token(
                 &self,
                 access_token: &AccessToken,
-            ) -> Result<Identity, IdentificationError>;
+            ) -> Result<(), AuthenticationError>;
         }
     }
 
@@ -125,13 +742,161 @@ mod tests {
                 &self,
                 signed_data: <MockMyOnChainRegistry as OnChainRegistry>::SignedData,
                 account_address: <MockMyOnChainRegistry as OnChainRegistry>::AccountAddress,
+                user_id: <MockMyOnChainRegistry as OnChainRegistry>::ContributorId,
+                nonce: Nonce,
+                operation: SignedOperation,
+            ) -> Result<(), SignatureError>;
+
+            async fn check_migration_signature(
+                &self,
+                signed_data: <MockMyOnChainRegistry as OnChainRegistry>::SignedData,
+                signer_address: <MockMyOnChainRegistry as OnChainRegistry>::AccountAddress,
+                old_account_address: <MockMyOnChainRegistry as OnChainRegistry>::AccountAddress,
+                new_account_address: <MockMyOnChainRegistry as OnChainRegistry>::AccountAddress,
+                user_id: <MockMyOnChainRegistry as OnChainRegistry>::ContributorId,
+                nonce: Nonce,
             ) -> Result<(), SignatureError>;
 
+            async fn get_registered_account(
+                &self,
+                user_id: <MockMyOnChainRegistry as OnChainRegistry>::ContributorId,
+            ) -> Result<Option<<MockMyOnChainRegistry as OnChainRegistry>::AccountAddress>, RegistryError>;
+
             async fn register_contributor(
                 &self,
                 user_account_address: <MockMyOnChainRegistry as OnChainRegistry>::AccountAddress,
                 user_id: <MockMyOnChainRegistry as OnChainRegistry>::ContributorId,
             ) -> Result<<MockMyOnChainRegistry as OnChainRegistry>::TransactionHash, RegistryError>;
+
+            async fn unregister_contributor(
+                &self,
+                user_account_address: <MockMyOnChainRegistry as OnChainRegistry>::AccountAddress,
+                user_id: <MockMyOnChainRegistry as OnChainRegistry>::ContributorId,
+            ) -> Result<<MockMyOnChainRegistry as OnChainRegistry>::TransactionHash, RegistryError>;
+
+            async fn migrate_contributor(
+                &self,
+                old_account_address: <MockMyOnChainRegistry as OnChainRegistry>::AccountAddress,
+                new_account_address: <MockMyOnChainRegistry as OnChainRegistry>::AccountAddress,
+                user_id: <MockMyOnChainRegistry as OnChainRegistry>::ContributorId,
+            ) -> Result<<MockMyOnChainRegistry as OnChainRegistry>::TransactionHash, RegistryError>;
+
+            async fn get_transaction_status(
+                &self,
+                transaction_hash: <MockMyOnChainRegistry as OnChainRegistry>::TransactionHash,
+            ) -> Result<TransactionStatus, RegistryError>;
+        }
+    }
+
+    mock! {
+        MyNonceStore {}
+        #[async_trait]
+        impl NonceStore for MyNonceStore {
+            async fn issue(&self, account_address: &str) -> Challenge;
+
+            async fn consume(&self, nonce: &Nonce, account_address: &str) -> Result<(), ChallengeError>;
+        }
+    }
+
+    mock! {
+        MyAuthorizationStateStore {}
+        #[async_trait]
+        impl AuthorizationStateStore for MyAuthorizationStateStore {
+            async fn issue(&self) -> AuthorizationState;
+            async fn consume(&self, state: &str) -> Result<AuthorizationState, AuthorizationStateError>;
+        }
+    }
+
+    mock! {
+        MyIdentitySessionStore {}
+        #[async_trait]
+        impl IdentitySessionStore for MyIdentitySessionStore {
+            async fn open(&self, identity: Identity, profile: IdentityProfile) -> IdentitySession;
+            async fn get(&self, id: &str) -> Result<IdentitySession, IdentitySessionError>;
+            async fn close(&self, id: &str) -> Result<IdentitySession, IdentitySessionError>;
+        }
+    }
+
+    mock! {
+        MyRegistrationRepository {}
+        #[async_trait]
+        impl RegistrationRepository for MyRegistrationRepository {
+            async fn create(&self, account_address: String) -> Result<Registration, RepositoryError>;
+
+            async fn save(&self, registration: &Registration) -> Result<(), RepositoryError>;
+
+            async fn find_by_identity(
+                &self,
+                identity: &Identity,
+            ) -> Result<Vec<Registration>, RepositoryError>;
+
+            async fn find_by_transaction_hash(
+                &self,
+                transaction_hash: &str,
+            ) -> Result<Option<Registration>, RepositoryError>;
+
+            async fn find_by_status(
+                &self,
+                status: RegistrationStatus,
+            ) -> Result<Vec<Registration>, RepositoryError>;
+        }
+    }
+
+    mock! {
+        MyEligibilityPolicy {}
+        impl EligibilityPolicy for MyEligibilityPolicy {
+            fn check(
+                &self,
+                identity: &Identity,
+                profile: &IdentityProfile,
+            ) -> Result<(), EligibilityError>;
+        }
+    }
+
+    fn eligible_policy() -> Box<MockMyEligibilityPolicy> {
+        let mut policy_mock = MockMyEligibilityPolicy::new();
+        policy_mock.expect_check().returning(|_, _| Ok(()));
+        Box::new(policy_mock)
+    }
+
+    fn authorization_code() -> Authorization {
+        Authorization::Code {
+            code: "foo-code".to_string(),
+            state: None,
+        }
+    }
+
+    fn foo_authorization_state() -> AuthorizationState {
+        AuthorizationState {
+            state: "foo-state".to_string(),
+            code_verifier: "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".to_string(),
+            expires_at: UNIX_EPOCH,
+        }
+    }
+
+    fn new_registration(account_address: String) -> Registration {
+        Registration {
+            id: 1,
+            status: RegistrationStatus::Started,
+            identity: None,
+            profile: None,
+            account_address,
+            transaction_hash: None,
+            error_kind: None,
+            created_at: UNIX_EPOCH,
+            updated_at: UNIX_EPOCH,
+        }
+    }
+
+    fn octocat_profile() -> IdentityProfile {
+        IdentityProfile {
+            login: "octocat".to_string(),
+            name: Some("monalisa octocat".to_string()),
+            avatar_url: Some("https://github.com/images/error/octocat_happy.gif".to_string()),
+            created_at: Some("2008-01-14T04:33:35Z".to_string()),
+            public_repos: Some(8),
+            followers: Some(20),
+            suspended: false,
         }
     }
 
@@ -141,15 +906,21 @@ mod tests {
 
         github_mock
             .expect_new_access_token()
-            .with(eq("foo-code"))
+            .with(eq("foo-code"), eq(None))
+            .times(1)
+            .returning(|_, _| Ok(AccessToken::from("foo-token".to_string())));
+
+        github_mock
+            .expect_get_user()
+            .with(eq(AccessToken::from("foo-token".to_string())))
             .times(1)
-            .returning(|_| Ok(AccessToken::from("foo-token".to_string())));
+            .returning(|_| Ok((Identity::GitHubId(42.into()), octocat_profile())));
 
         github_mock
-            .expect_get_user_id()
+            .expect_revoke_access_token()
             .with(eq(AccessToken::from("foo-token".to_string())))
             .times(1)
-            .returning(|_| Ok(Identity::GitHubId(42.into())));
+            .returning(|_| Ok(()));
 
         let mut registry_mock = MockMyOnChainRegistry::new();
 
@@ -160,21 +931,26 @@ mod tests {
                     hash: felt!(
                         "0x287b943b1934949486006ad63ac0293038b6c818b858b09f8e0a9da12fc4074"
                     ),
-                    signature: StarknetSignature {
-                        r: felt!(
-                            "0xde4d49b21dd8714eaf5a1b480d8ede84d2230d1763cfe06762d8a117493bcd"
-                        ),
-                        s: felt!(
-                            "0x4b61402b98b29a34bd4cba8b5eabae840809914160002385444059f59449a4"
-                        ),
-                    },
+                    signature: StarknetSignature(vec![
+                        felt!("0xde4d49b21dd8714eaf5a1b480d8ede84d2230d1763cfe06762d8a117493bcd"),
+                        felt!("0x4b61402b98b29a34bd4cba8b5eabae840809914160002385444059f59449a4"),
+                    ]),
                 }),
                 eq(felt!(
                     "0x65f1506b7f974a1355aeebc1314579326c84a029cd8257a91f82384a6a0ace"
                 )),
+                eq(FieldElement::from(42u32)),
+                eq(Nonce::from(7)),
+                eq(SignedOperation::Registration),
             )
             .times(1)
-            .returning(|_, _| Ok(()));
+            .returning(|_, _, _, _, _| Ok(()));
+
+        registry_mock
+            .expect_get_registered_account()
+            .with(eq(FieldElement::from(42u32)))
+            .times(1)
+            .returning(|_| Ok(None));
 
         registry_mock
             .expect_register_contributor()
@@ -187,28 +963,1318 @@ mod tests {
             .times(1)
             .returning(|_, _| Ok(felt!("0x666")));
 
-        let registerer = RegistererImpl::new(github_mock, registry_mock);
+        let mut nonce_store_mock = MockMyNonceStore::new();
+
+        nonce_store_mock
+            .expect_consume()
+            .with(
+                eq(Nonce::from(7)),
+                eq("0x65f1506b7f974a1355aeebc1314579326c84a029cd8257a91f82384a6a0ace"),
+            )
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+
+        repository_mock
+            .expect_create()
+            .with(eq(
+                "0x65f1506b7f974a1355aeebc1314579326c84a029cd8257a91f82384a6a0ace".to_string(),
+            ))
+            .times(1)
+            .returning(|account_address| Ok(new_registration(account_address)));
+
+        repository_mock
+            .expect_find_by_identity()
+            .with(eq(Identity::GitHubId(42.into())))
+            .times(1)
+            .returning(|_| Ok(vec![]));
+
+        repository_mock
+            .expect_save()
+            .withf(|registration| {
+                registration.status == RegistrationStatus::Submitted
+                    && registration.identity == Some(Identity::GitHubId(42.into()))
+                    && registration.profile == Some(octocat_profile())
+                    && registration.transaction_hash == Some("0x666".to_string())
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let (tracker_handle, mut tracker_receiver) = confirmation_tracker::channel();
+
+        let registerer = RegistererImpl::new(
+            github_mock,
+            Arc::new(registry_mock),
+            Arc::new(nonce_store_mock),
+            Arc::new(repository_mock),
+            tracker_handle,
+            eligible_policy(),
+        );
 
         let registration = registerer
             .register_contributor(
-                "foo-code".to_string(),
+                authorization_code(),
                 felt!("0x65f1506b7f974a1355aeebc1314579326c84a029cd8257a91f82384a6a0ace"),
                 StarknetSignedData {
                     hash: felt!(
                         "0x287b943b1934949486006ad63ac0293038b6c818b858b09f8e0a9da12fc4074"
                     ),
-                    signature: StarknetSignature {
-                        r: felt!(
-                            "0xde4d49b21dd8714eaf5a1b480d8ede84d2230d1763cfe06762d8a117493bcd"
-                        ),
-                        s: felt!(
-                            "0x4b61402b98b29a34bd4cba8b5eabae840809914160002385444059f59449a4"
-                        ),
-                    },
+                    signature: StarknetSignature(vec![
+                        felt!("0xde4d49b21dd8714eaf5a1b480d8ede84d2230d1763cfe06762d8a117493bcd"),
+                        felt!("0x4b61402b98b29a34bd4cba8b5eabae840809914160002385444059f59449a4"),
+                    ]),
+                },
+                Nonce::from(7),
+            )
+            .await;
+
+        assert_ok_eq!(
+            registration,
+            (
+                RegistrationOutcome::Submitted(felt!("0x666")),
+                octocat_profile()
+            )
+        );
+
+        let pending = tracker_receiver.try_recv().unwrap();
+        assert_eq!(pending.transaction_hash, felt!("0x666"));
+        assert_eq!(pending.user_id, FieldElement::from(42u32));
+        assert_eq!(pending.attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_register_github_user_with_expired_nonce() {
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_check_signature()
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
+        registry_mock.expect_get_registered_account().never();
+        registry_mock.expect_register_contributor().never();
+
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock
+            .expect_consume()
+            .with(
+                eq(Nonce::from(7)),
+                eq("0x65f1506b7f974a1355aeebc1314579326c84a029cd8257a91f82384a6a0ace"),
+            )
+            .times(1)
+            .returning(|_, _| Err(ChallengeError::Expired));
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+
+        repository_mock
+            .expect_create()
+            .times(1)
+            .returning(|account_address| Ok(new_registration(account_address)));
+
+        repository_mock
+            .expect_save()
+            .withf(|registration| {
+                registration.status == RegistrationStatus::Failed
+                    && registration.identity == Some(Identity::GitHubId(42.into()))
+                    && registration.error_kind == Some("challenge".to_string())
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let (tracker_handle, mut tracker_receiver) = confirmation_tracker::channel();
+
+        let registerer = RegistererImpl::new(
+            new_identified_github_mock(),
+            Arc::new(registry_mock),
+            Arc::new(nonce_store_mock),
+            Arc::new(repository_mock),
+            tracker_handle,
+            eligible_policy(),
+        );
+
+        let registration = registerer
+            .register_contributor(
+                authorization_code(),
+                felt!("0x65f1506b7f974a1355aeebc1314579326c84a029cd8257a91f82384a6a0ace"),
+                StarknetSignedData {
+                    hash: felt!("0x1"),
+                    signature: StarknetSignature(vec![felt!("0x2"), felt!("0x3")]),
+                },
+                Nonce::from(7),
+            )
+            .await;
+
+        assert!(matches!(
+            registration,
+            Err(RegistrationError::Challenge(ChallengeError::Expired))
+        ));
+        assert!(tracker_receiver.try_recv().is_err());
+    }
+
+    fn new_identified_github_mock() -> MockMyIdentityProvider {
+        let mut github_mock = MockMyIdentityProvider::new();
+        github_mock
+            .expect_new_access_token()
+            .returning(|_, _| Ok(AccessToken::from("foo-token".to_string())));
+        github_mock
+            .expect_get_user()
+            .returning(|_| Ok((Identity::GitHubId(42.into()), octocat_profile())));
+        github_mock
+            .expect_revoke_access_token()
+            .returning(|_| Ok(()));
+        github_mock
+    }
+
+    fn new_signed_data() -> StarknetSignedData {
+        StarknetSignedData {
+            hash: felt!("0x1"),
+            signature: StarknetSignature(vec![felt!("0x2"), felt!("0x3")]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_github_user_already_registered_with_same_account() {
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_check_signature()
+            .returning(|_, _, _, _, _| Ok(()));
+        registry_mock
+            .expect_get_registered_account()
+            .with(eq(FieldElement::from(42u32)))
+            .times(1)
+            .returning(|_| Ok(Some(felt!("0x65f1"))));
+        registry_mock.expect_register_contributor().never();
+
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock.expect_consume().returning(|_, _| Ok(()));
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_create()
+            .times(1)
+            .returning(|account_address| Ok(new_registration(account_address)));
+        repository_mock
+            .expect_find_by_identity()
+            .with(eq(Identity::GitHubId(42.into())))
+            .times(1)
+            .returning(|_| {
+                let mut accepted = new_registration("0x65f1".to_string());
+                accepted.identity = Some(Identity::GitHubId(42.into()));
+                accepted.submit("0x666".to_string());
+                accepted.accept();
+                Ok(vec![accepted])
+            });
+        repository_mock
+            .expect_save()
+            .withf(|registration| {
+                registration.status == RegistrationStatus::AlreadyRegistered
+                    && registration.transaction_hash == Some("0x666".to_string())
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let (tracker_handle, mut tracker_receiver) = confirmation_tracker::channel();
+
+        let registerer = RegistererImpl::new(
+            new_identified_github_mock(),
+            Arc::new(registry_mock),
+            Arc::new(nonce_store_mock),
+            Arc::new(repository_mock),
+            tracker_handle,
+            eligible_policy(),
+        );
+
+        let registration = registerer
+            .register_contributor(
+                authorization_code(),
+                felt!("0x65f1"),
+                new_signed_data(),
+                Nonce::from(7),
+            )
+            .await;
+
+        assert_ok_eq!(
+            registration,
+            (
+                RegistrationOutcome::AlreadyRegistered {
+                    transaction_hash: Some(felt!("0x666"))
+                },
+                octocat_profile()
+            )
+        );
+        assert!(tracker_receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_register_github_user_while_first_registration_is_pending() {
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_check_signature()
+            .returning(|_, _, _, _, _| Ok(()));
+        // the first transaction is not accepted yet
+        registry_mock
+            .expect_get_registered_account()
+            .with(eq(FieldElement::from(42u32)))
+            .times(1)
+            .returning(|_| Ok(None));
+        registry_mock.expect_register_contributor().never();
+
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock.expect_consume().returning(|_, _| Ok(()));
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_create()
+            .times(1)
+            .returning(|account_address| Ok(new_registration(account_address)));
+        repository_mock
+            .expect_find_by_identity()
+            .with(eq(Identity::GitHubId(42.into())))
+            .returning(|_| {
+                let mut submitted = new_registration("0x65f1".to_string());
+                submitted.identity = Some(Identity::GitHubId(42.into()));
+                submitted.submit("0x666".to_string());
+                Ok(vec![submitted])
+            });
+        repository_mock
+            .expect_save()
+            .withf(|registration| {
+                registration.status == RegistrationStatus::AlreadyRegistered
+                    && registration.transaction_hash == Some("0x666".to_string())
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let (tracker_handle, mut tracker_receiver) = confirmation_tracker::channel();
+
+        let registerer = RegistererImpl::new(
+            new_identified_github_mock(),
+            Arc::new(registry_mock),
+            Arc::new(nonce_store_mock),
+            Arc::new(repository_mock),
+            tracker_handle,
+            eligible_policy(),
+        );
+
+        let registration = registerer
+            .register_contributor(
+                authorization_code(),
+                felt!("0x65f1"),
+                new_signed_data(),
+                Nonce::from(7),
+            )
+            .await;
+
+        assert_ok_eq!(
+            registration,
+            (
+                RegistrationOutcome::AlreadyRegistered {
+                    transaction_hash: Some(felt!("0x666"))
+                },
+                octocat_profile()
+            )
+        );
+        assert!(tracker_receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_register_github_user_already_registered_with_another_account() {
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_check_signature()
+            .returning(|_, _, _, _, _| Ok(()));
+        registry_mock
+            .expect_get_registered_account()
+            .with(eq(FieldElement::from(42u32)))
+            .times(1)
+            .returning(|_| Ok(Some(felt!("0x777"))));
+        registry_mock.expect_register_contributor().never();
+
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock.expect_consume().returning(|_, _| Ok(()));
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_create()
+            .times(1)
+            .returning(|account_address| Ok(new_registration(account_address)));
+        repository_mock
+            .expect_save()
+            .withf(|registration| {
+                registration.status == RegistrationStatus::Failed
+                    && registration.error_kind == Some("already_registered".to_string())
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let registerer = RegistererImpl::new(
+            new_identified_github_mock(),
+            Arc::new(registry_mock),
+            Arc::new(nonce_store_mock),
+            Arc::new(repository_mock),
+            confirmation_tracker::channel().0,
+            eligible_policy(),
+        );
+
+        let registration = registerer
+            .register_contributor(
+                authorization_code(),
+                felt!("0x65f1"),
+                new_signed_data(),
+                Nonce::from(7),
+            )
+            .await;
+
+        assert!(matches!(
+            registration,
+            Err(RegistrationError::AlreadyRegistered)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_register_ineligible_github_user() {
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock.expect_check_signature().never();
+        registry_mock.expect_register_contributor().never();
+
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock.expect_consume().never();
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_create()
+            .times(1)
+            .returning(|account_address| Ok(new_registration(account_address)));
+        repository_mock
+            .expect_save()
+            .withf(|registration| {
+                registration.status == RegistrationStatus::Failed
+                    && registration.identity == Some(Identity::GitHubId(42.into()))
+                    && registration.error_kind == Some("account_too_recent".to_string())
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut policy_mock = MockMyEligibilityPolicy::new();
+        policy_mock
+            .expect_check()
+            .with(eq(Identity::GitHubId(42.into())), eq(octocat_profile()))
+            .times(1)
+            .returning(|_, _| Err(EligibilityError::AccountTooRecent));
+
+        let registerer = RegistererImpl::new(
+            new_identified_github_mock(),
+            Arc::new(registry_mock),
+            Arc::new(nonce_store_mock),
+            Arc::new(repository_mock),
+            confirmation_tracker::channel().0,
+            Box::new(policy_mock),
+        );
+
+        let registration = registerer
+            .register_contributor(
+                authorization_code(),
+                felt!("0x65f1"),
+                new_signed_data(),
+                Nonce::from(7),
+            )
+            .await;
+
+        assert!(matches!(
+            registration,
+            Err(RegistrationError::AccountTooRecent)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_register_github_user_member_of_a_required_team() {
+        let mut github_mock = new_identified_github_mock();
+        github_mock
+            .expect_get_memberships()
+            .with(eq(AccessToken::from("foo-token".to_string())))
+            .times(1)
+            .returning(|_| Ok(vec!["onlydustxyz".to_string(), "partner/core".to_string()]));
+
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_check_signature()
+            .returning(|_, _, _, _, _| Ok(()));
+        registry_mock
+            .expect_get_registered_account()
+            .returning(|_| Ok(None));
+        registry_mock
+            .expect_register_contributor()
+            .times(1)
+            .returning(|_, _| Ok(felt!("0x666")));
+
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock.expect_consume().returning(|_, _| Ok(()));
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_create()
+            .returning(|account_address| Ok(new_registration(account_address)));
+        repository_mock
+            .expect_find_by_identity()
+            .returning(|_| Ok(vec![]));
+        repository_mock.expect_save().returning(|_| Ok(()));
+
+        let registerer = RegistererImpl::new(
+            github_mock,
+            Arc::new(registry_mock),
+            Arc::new(nonce_store_mock),
+            Arc::new(repository_mock),
+            confirmation_tracker::channel().0,
+            eligible_policy(),
+        )
+        .with_required_memberships(vec!["Partner/Core".to_string()]);
+
+        let registration = registerer
+            .register_contributor(
+                authorization_code(),
+                felt!("0x65f1"),
+                new_signed_data(),
+                Nonce::from(7),
+            )
+            .await;
+
+        assert!(registration.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_register_github_user_not_member_of_required_organizations() {
+        let mut github_mock = new_identified_github_mock();
+        github_mock
+            .expect_get_memberships()
+            .times(1)
+            .returning(|_| Ok(vec!["onlydustxyz".to_string()]));
+
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock.expect_check_signature().never();
+        registry_mock.expect_register_contributor().never();
+
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock.expect_consume().returning(|_, _| Ok(()));
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_create()
+            .returning(|account_address| Ok(new_registration(account_address)));
+        repository_mock
+            .expect_save()
+            .withf(|registration| registration.error_kind == Some("not_a_member".to_string()))
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let registerer = RegistererImpl::new(
+            github_mock,
+            Arc::new(registry_mock),
+            Arc::new(nonce_store_mock),
+            Arc::new(repository_mock),
+            confirmation_tracker::channel().0,
+            eligible_policy(),
+        )
+        .with_required_memberships(vec!["partner".to_string(), "other/team".to_string()]);
+
+        let registration = registerer
+            .register_contributor(
+                authorization_code(),
+                felt!("0x65f1"),
+                new_signed_data(),
+                Nonce::from(7),
+            )
+            .await;
+
+        assert!(matches!(registration, Err(RegistrationError::NotAMember)));
+    }
+
+    #[tokio::test]
+    async fn test_register_github_user_revokes_access_token_on_identification_failure() {
+        let mut github_mock = MockMyIdentityProvider::new();
+        github_mock
+            .expect_new_access_token()
+            .returning(|_, _| Ok(AccessToken::from("foo-token".to_string())));
+        github_mock
+            .expect_get_user()
+            .returning(|_| Err(IdentificationError::Http("bad gateway".into())));
+        github_mock
+            .expect_revoke_access_token()
+            .with(eq(AccessToken::from("foo-token".to_string())))
+            .times(1)
+            .returning(|_| Err(AuthenticationError::Http("not found".into())));
+
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock.expect_register_contributor().never();
+
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock.expect_consume().never();
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_create()
+            .returning(|account_address| Ok(new_registration(account_address)));
+        repository_mock
+            .expect_save()
+            .withf(|registration| registration.error_kind == Some("identification".to_string()))
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let registerer = RegistererImpl::new(
+            github_mock,
+            Arc::new(registry_mock),
+            Arc::new(nonce_store_mock),
+            Arc::new(repository_mock),
+            confirmation_tracker::channel().0,
+            eligible_policy(),
+        );
+
+        let registration = registerer
+            .register_contributor(
+                authorization_code(),
+                felt!("0x65f1"),
+                new_signed_data(),
+                Nonce::from(7),
+            )
+            .await;
+
+        assert!(matches!(
+            registration,
+            Err(RegistrationError::Identification(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_register_github_user_with_access_token() {
+        let mut github_mock = MockMyIdentityProvider::new();
+        github_mock.expect_new_access_token().never();
+        github_mock
+            .expect_get_user()
+            .with(eq(AccessToken::from("ghu_token".to_string())))
+            .times(1)
+            .returning(|_| Err(IdentificationError::Http("bad gateway".into())));
+        github_mock.expect_revoke_access_token().never();
+
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock.expect_consume().returning(|_, _| Ok(()));
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_create()
+            .returning(|account_address| Ok(new_registration(account_address)));
+        repository_mock.expect_save().returning(|_| Ok(()));
+
+        let registerer = RegistererImpl::new(
+            github_mock,
+            Arc::new(MockMyOnChainRegistry::new()),
+            Arc::new(nonce_store_mock),
+            Arc::new(repository_mock),
+            confirmation_tracker::channel().0,
+            eligible_policy(),
+        );
+
+        let registration = registerer
+            .register_contributor(
+                Authorization::AccessToken(AccessToken::from("ghu_token".to_string())),
+                felt!("0x65f1"),
+                new_signed_data(),
+                Nonce::from(7),
+            )
+            .await;
+
+        assert!(matches!(
+            registration,
+            Err(RegistrationError::Identification(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_register_github_user_with_authorization_state() {
+        let mut github_mock = MockMyIdentityProvider::new();
+        github_mock
+            .expect_new_access_token()
+            .with(
+                eq("foo-code"),
+                eq(Some(
+                    "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".to_string(),
+                )),
+            )
+            .times(1)
+            .returning(|_, _| {
+                Err(AuthenticationError::Rejected(
+                    "bad_verification_code".into(),
+                ))
+            });
+
+        let mut state_store_mock = MockMyAuthorizationStateStore::new();
+        state_store_mock
+            .expect_consume()
+            .with(eq("foo-state"))
+            .times(1)
+            .returning(|_| Ok(foo_authorization_state()));
+
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock.expect_consume().returning(|_, _| Ok(()));
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_create()
+            .returning(|account_address| Ok(new_registration(account_address)));
+        repository_mock.expect_save().returning(|_| Ok(()));
+
+        let registerer = RegistererImpl::new(
+            github_mock,
+            Arc::new(MockMyOnChainRegistry::new()),
+            Arc::new(nonce_store_mock),
+            Arc::new(repository_mock),
+            confirmation_tracker::channel().0,
+            eligible_policy(),
+        )
+        .with_authorization_states(Arc::new(state_store_mock));
+
+        let registration = registerer
+            .register_contributor(
+                Authorization::Code {
+                    code: "foo-code".to_string(),
+                    state: Some("foo-state".to_string()),
                 },
+                felt!("0x65f1"),
+                new_signed_data(),
+                Nonce::from(7),
+            )
+            .await;
+
+        assert!(matches!(
+            registration,
+            Err(RegistrationError::Authentication(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_register_github_user_without_authorization_state() {
+        let mut github_mock = MockMyIdentityProvider::new();
+        github_mock.expect_new_access_token().never();
+
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock.expect_consume().returning(|_, _| Ok(()));
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_create()
+            .returning(|account_address| Ok(new_registration(account_address)));
+        repository_mock
+            .expect_save()
+            .withf(|registration| registration.error_kind == Some("state".to_string()))
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let registerer = RegistererImpl::new(
+            github_mock,
+            Arc::new(MockMyOnChainRegistry::new()),
+            Arc::new(nonce_store_mock),
+            Arc::new(repository_mock),
+            confirmation_tracker::channel().0,
+            eligible_policy(),
+        )
+        .with_authorization_states(Arc::new(MockMyAuthorizationStateStore::new()));
+
+        let registration = registerer
+            .register_contributor(
+                authorization_code(),
+                felt!("0x65f1"),
+                new_signed_data(),
+                Nonce::from(7),
+            )
+            .await;
+
+        assert!(matches!(
+            registration,
+            Err(RegistrationError::State(AuthorizationStateError::Missing))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_register_github_user_without_authorization_state_when_allowed() {
+        let mut github_mock = MockMyIdentityProvider::new();
+        github_mock
+            .expect_new_access_token()
+            .with(eq("foo-code"), eq(None))
+            .times(1)
+            .returning(|_, _| {
+                Err(AuthenticationError::Rejected(
+                    "bad_verification_code".into(),
+                ))
+            });
+
+        let mut state_store_mock = MockMyAuthorizationStateStore::new();
+        state_store_mock.expect_consume().never();
+
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock.expect_consume().returning(|_, _| Ok(()));
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_create()
+            .returning(|account_address| Ok(new_registration(account_address)));
+        repository_mock.expect_save().returning(|_| Ok(()));
+
+        let registerer = RegistererImpl::new(
+            github_mock,
+            Arc::new(MockMyOnChainRegistry::new()),
+            Arc::new(nonce_store_mock),
+            Arc::new(repository_mock),
+            confirmation_tracker::channel().0,
+            eligible_policy(),
+        )
+        .with_authorization_states(Arc::new(state_store_mock))
+        .with_codes_without_state();
+
+        let registration = registerer
+            .register_contributor(
+                authorization_code(),
+                felt!("0x65f1"),
+                new_signed_data(),
+                Nonce::from(7),
+            )
+            .await;
+
+        // the code is exchanged without any PKCE code verifier
+        assert!(matches!(
+            registration,
+            Err(RegistrationError::Authentication(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_new_challenge() {
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock
+            .expect_issue()
+            .with(eq("0x65f1"))
+            .times(1)
+            .returning(|_| Challenge {
+                nonce: Nonce::from(7),
+                expires_at: UNIX_EPOCH,
+            });
+
+        let registerer = RegistererImpl::new(
+            MockMyIdentityProvider::new(),
+            Arc::new(MockMyOnChainRegistry::new()),
+            Arc::new(nonce_store_mock),
+            Arc::new(MockMyRegistrationRepository::new()),
+            confirmation_tracker::channel().0,
+            eligible_policy(),
+        );
+
+        let challenge = registerer.new_challenge(felt!("0x65f1")).await;
+
+        assert_eq!(challenge.nonce, Nonce::from(7));
+    }
+
+    #[tokio::test]
+    async fn test_new_authorization_url() {
+        let mut github_mock = MockMyIdentityProvider::new();
+        github_mock
+            .expect_authorization_url()
+            .with(
+                eq("foo-state"),
+                eq("E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"),
+            )
+            .times(1)
+            .returning(|_, _| Some("https://github.com/login/oauth/authorize".to_string()));
+
+        let mut state_store_mock = MockMyAuthorizationStateStore::new();
+        state_store_mock
+            .expect_issue()
+            .times(1)
+            .returning(foo_authorization_state);
+
+        let registerer = RegistererImpl::new(
+            github_mock,
+            Arc::new(MockMyOnChainRegistry::new()),
+            Arc::new(MockMyNonceStore::new()),
+            Arc::new(MockMyRegistrationRepository::new()),
+            confirmation_tracker::channel().0,
+            eligible_policy(),
+        )
+        .with_authorization_states(Arc::new(state_store_mock));
+
+        assert_eq!(
+            registerer.new_authorization_url().await,
+            Some("https://github.com/login/oauth/authorize".to_string())
+        );
+    }
+
+    fn octocat_session() -> IdentitySession {
+        IdentitySession {
+            id: "foo-session".to_string(),
+            identity: Identity::GitHubId(42.into()),
+            profile: octocat_profile(),
+            expires_at: UNIX_EPOCH,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_open_session() {
+        let mut session_store_mock = MockMyIdentitySessionStore::new();
+        session_store_mock
+            .expect_open()
+            .with(eq(Identity::GitHubId(42.into())), eq(octocat_profile()))
+            .times(1)
+            .returning(|_, _| octocat_session());
+
+        let registerer = RegistererImpl::new(
+            new_identified_github_mock(),
+            Arc::new(MockMyOnChainRegistry::new()),
+            Arc::new(MockMyNonceStore::new()),
+            Arc::new(MockMyRegistrationRepository::new()),
+            confirmation_tracker::channel().0,
+            eligible_policy(),
+        )
+        .with_identity_sessions(Arc::new(session_store_mock));
+
+        let session = registerer.open_session(authorization_code()).await;
+
+        assert_ok_eq!(session, octocat_session());
+    }
+
+    #[tokio::test]
+    async fn test_open_session_of_ineligible_user() {
+        let mut session_store_mock = MockMyIdentitySessionStore::new();
+        session_store_mock.expect_open().never();
+
+        let mut policy_mock = MockMyEligibilityPolicy::new();
+        policy_mock
+            .expect_check()
+            .returning(|_, _| Err(EligibilityError::AccountTooRecent));
+
+        let registerer = RegistererImpl::new(
+            new_identified_github_mock(),
+            Arc::new(MockMyOnChainRegistry::new()),
+            Arc::new(MockMyNonceStore::new()),
+            Arc::new(MockMyRegistrationRepository::new()),
+            confirmation_tracker::channel().0,
+            Box::new(policy_mock),
+        )
+        .with_identity_sessions(Arc::new(session_store_mock));
+
+        let session = registerer.open_session(authorization_code()).await;
+
+        assert!(matches!(session, Err(RegistrationError::AccountTooRecent)));
+    }
+
+    #[tokio::test]
+    async fn test_register_github_user_with_session() {
+        let mut github_mock = MockMyIdentityProvider::new();
+        github_mock.expect_new_access_token().never();
+        github_mock.expect_get_user().never();
+
+        let mut session_store_mock = MockMyIdentitySessionStore::new();
+        session_store_mock
+            .expect_get()
+            .with(eq("foo-session"))
+            .times(1)
+            .returning(|_| Ok(octocat_session()));
+        session_store_mock
+            .expect_close()
+            .with(eq("foo-session"))
+            .times(1)
+            .returning(|_| Ok(octocat_session()));
+
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_check_signature()
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
+        registry_mock
+            .expect_get_registered_account()
+            .returning(|_| Ok(None));
+        registry_mock
+            .expect_register_contributor()
+            .times(1)
+            .returning(|_, _| Ok(felt!("0x666")));
+
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock
+            .expect_consume()
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_create()
+            .returning(|account_address| Ok(new_registration(account_address)));
+        repository_mock
+            .expect_find_by_identity()
+            .returning(|_| Ok(vec![]));
+        repository_mock.expect_save().returning(|_| Ok(()));
+
+        let registerer = RegistererImpl::new(
+            github_mock,
+            Arc::new(registry_mock),
+            Arc::new(nonce_store_mock),
+            Arc::new(repository_mock),
+            confirmation_tracker::channel().0,
+            eligible_policy(),
+        )
+        .with_identity_sessions(Arc::new(session_store_mock));
+
+        let registration = registerer
+            .register_contributor(
+                Authorization::Session("foo-session".to_string()),
+                felt!("0x65f1"),
+                new_signed_data(),
+                Nonce::from(7),
+            )
+            .await;
+
+        assert_ok_eq!(
+            registration,
+            (
+                RegistrationOutcome::Submitted(felt!("0x666")),
+                octocat_profile()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_register_github_user_with_session_and_invalid_signature() {
+        let mut github_mock = MockMyIdentityProvider::new();
+        github_mock.expect_new_access_token().never();
+        github_mock.expect_get_user().never();
+
+        let mut session_store_mock = MockMyIdentitySessionStore::new();
+        session_store_mock
+            .expect_get()
+            .with(eq("foo-session"))
+            .times(1)
+            .returning(|_| Ok(octocat_session()));
+        // the user can sign again without going through GitHub
+        session_store_mock.expect_close().never();
+
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_check_signature()
+            .with(
+                always(),
+                always(),
+                eq(FieldElement::from(42u32)),
+                always(),
+                always(),
+            )
+            .times(1)
+            .returning(|_, _, _, _, _| {
+                Err(SignatureError::InvalidSignature("invalid signature".into()))
+            });
+
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock.expect_consume().never();
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_create()
+            .returning(|account_address| Ok(new_registration(account_address)));
+        repository_mock.expect_save().returning(|_| Ok(()));
+
+        let registerer = RegistererImpl::new(
+            github_mock,
+            Arc::new(registry_mock),
+            Arc::new(nonce_store_mock),
+            Arc::new(repository_mock),
+            confirmation_tracker::channel().0,
+            eligible_policy(),
+        )
+        .with_identity_sessions(Arc::new(session_store_mock));
+
+        let registration = registerer
+            .register_contributor(
+                Authorization::Session("foo-session".to_string()),
+                felt!("0x65f1"),
+                new_signed_data(),
+                Nonce::from(7),
             )
             .await;
 
-        assert_ok_eq!(registration, felt!("0x666"));
+        assert!(matches!(registration, Err(RegistrationError::Signature(_))));
+    }
+
+    #[tokio::test]
+    async fn test_unregister_github_user() {
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_check_signature()
+            .with(
+                eq(new_signed_data()),
+                eq(felt!("0x65f1")),
+                eq(FieldElement::from(42u32)),
+                eq(Nonce::from(7)),
+                eq(SignedOperation::Unregistration),
+            )
+            .times(1)
+            .returning(|_, _, _, _, _| Ok(()));
+        registry_mock
+            .expect_get_registered_account()
+            .with(eq(FieldElement::from(42u32)))
+            .times(1)
+            .returning(|_| Ok(Some(felt!("0x65f1"))));
+        registry_mock
+            .expect_unregister_contributor()
+            .with(eq(felt!("0x65f1")), eq(FieldElement::from(42u32)))
+            .times(1)
+            .returning(|_, _| Ok(felt!("0x666")));
+
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock
+            .expect_consume()
+            .with(eq(Nonce::from(7)), eq("0x65f1"))
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let registerer = RegistererImpl::new(
+            new_identified_github_mock(),
+            Arc::new(registry_mock),
+            Arc::new(nonce_store_mock),
+            Arc::new(MockMyRegistrationRepository::new()),
+            confirmation_tracker::channel().0,
+            Box::new(MockMyEligibilityPolicy::new()),
+        );
+
+        let result = registerer
+            .unregister_contributor(
+                authorization_code(),
+                felt!("0x65f1"),
+                new_signed_data(),
+                Nonce::from(7),
+            )
+            .await;
+
+        assert_ok_eq!(result, felt!("0x666"));
+    }
+
+    #[tokio::test]
+    async fn test_unregister_github_user_registered_with_another_account() {
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_check_signature()
+            .returning(|_, _, _, _, _| Ok(()));
+        registry_mock
+            .expect_get_registered_account()
+            .returning(|_| Ok(Some(felt!("0x777"))));
+        registry_mock.expect_unregister_contributor().never();
+
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock.expect_consume().returning(|_, _| Ok(()));
+
+        let registerer = RegistererImpl::new(
+            new_identified_github_mock(),
+            Arc::new(registry_mock),
+            Arc::new(nonce_store_mock),
+            Arc::new(MockMyRegistrationRepository::new()),
+            confirmation_tracker::channel().0,
+            Box::new(MockMyEligibilityPolicy::new()),
+        );
+
+        let result = registerer
+            .unregister_contributor(
+                authorization_code(),
+                felt!("0x65f1"),
+                new_signed_data(),
+                Nonce::from(7),
+            )
+            .await;
+
+        assert!(matches!(result, Err(RegistrationError::NotRegistered)));
+    }
+
+    #[tokio::test]
+    async fn test_migrate_github_user() {
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_check_migration_signature()
+            .with(
+                eq(new_signed_data()),
+                eq(felt!("0x65f2")),
+                eq(felt!("0x65f1")),
+                eq(felt!("0x65f2")),
+                eq(FieldElement::from(42u32)),
+                eq(Nonce::from(7)),
+            )
+            .times(1)
+            .returning(|_, _, _, _, _, _| Ok(()));
+        registry_mock
+            .expect_get_registered_account()
+            .with(eq(FieldElement::from(42u32)))
+            .times(1)
+            .returning(|_| Ok(Some(felt!("0x65f1"))));
+        registry_mock
+            .expect_migrate_contributor()
+            .with(
+                eq(felt!("0x65f1")),
+                eq(felt!("0x65f2")),
+                eq(FieldElement::from(42u32)),
+            )
+            .times(1)
+            .returning(|_, _, _| Ok(felt!("0x666")));
+
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock
+            .expect_consume()
+            .with(eq(Nonce::from(7)), eq("0x65f2"))
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let registerer = RegistererImpl::new(
+            new_identified_github_mock(),
+            Arc::new(registry_mock),
+            Arc::new(nonce_store_mock),
+            Arc::new(MockMyRegistrationRepository::new()),
+            confirmation_tracker::channel().0,
+            Box::new(MockMyEligibilityPolicy::new()),
+        );
+
+        let result = registerer
+            .migrate_contributor(
+                authorization_code(),
+                felt!("0x65f1"),
+                felt!("0x65f2"),
+                new_signed_data(),
+                None,
+                Nonce::from(7),
+            )
+            .await;
+
+        assert_ok_eq!(result, felt!("0x666"));
+    }
+
+    #[tokio::test]
+    async fn test_migrate_github_user_with_invalid_old_account_signature() {
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_check_migration_signature()
+            .with(
+                always(),
+                eq(felt!("0x65f2")),
+                eq(felt!("0x65f1")),
+                eq(felt!("0x65f2")),
+                always(),
+                always(),
+            )
+            .times(1)
+            .returning(|_, _, _, _, _, _| Ok(()));
+        registry_mock
+            .expect_check_migration_signature()
+            .with(
+                always(),
+                eq(felt!("0x65f1")),
+                eq(felt!("0x65f1")),
+                eq(felt!("0x65f2")),
+                always(),
+                always(),
+            )
+            .times(1)
+            .returning(|_, _, _, _, _, _| Err(SignatureError::UnexpectedHash));
+        registry_mock.expect_migrate_contributor().never();
+
+        let mut nonce_store_mock = MockMyNonceStore::new();
+        nonce_store_mock.expect_consume().never();
+
+        let registerer = RegistererImpl::new(
+            new_identified_github_mock(),
+            Arc::new(registry_mock),
+            Arc::new(nonce_store_mock),
+            Arc::new(MockMyRegistrationRepository::new()),
+            confirmation_tracker::channel().0,
+            Box::new(MockMyEligibilityPolicy::new()),
+        );
+
+        let result = registerer
+            .migrate_contributor(
+                authorization_code(),
+                felt!("0x65f1"),
+                felt!("0x65f2"),
+                new_signed_data(),
+                Some(new_signed_data()),
+                Nonce::from(7),
+            )
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(RegistrationError::Signature(SignatureError::UnexpectedHash))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_registration_status() {
+        let mut registry_mock = MockMyOnChainRegistry::new();
+
+        registry_mock
+            .expect_get_transaction_status()
+            .with(eq(felt!("0x666")))
+            .times(1)
+            .returning(|_| {
+                Ok(TransactionStatus::Rejected {
+                    reason: Some("out of gas".to_string()),
+                })
+            });
+
+        let registerer = RegistererImpl::new(
+            MockMyIdentityProvider::new(),
+            Arc::new(registry_mock),
+            Arc::new(MockMyNonceStore::new()),
+            Arc::new(MockMyRegistrationRepository::new()),
+            confirmation_tracker::channel().0,
+            Box::new(MockMyEligibilityPolicy::new()),
+        );
+
+        let status = registerer.get_registration_status(felt!("0x666")).await;
+
+        assert_ok_eq!(
+            status,
+            TransactionStatus::Rejected {
+                reason: Some("out of gas".to_string())
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_registration_status_of_unknown_transaction() {
+        let mut registry_mock = MockMyOnChainRegistry::new();
+        registry_mock
+            .expect_get_transaction_status()
+            .returning(|_| Ok(TransactionStatus::NotReceived));
+
+        let mut repository_mock = MockMyRegistrationRepository::new();
+        repository_mock
+            .expect_find_by_transaction_hash()
+            .with(eq("0x666"))
+            .times(1)
+            .returning(|_| Ok(None));
+        repository_mock
+            .expect_find_by_transaction_hash()
+            .with(eq("0x777"))
+            .times(1)
+            .returning(|transaction_hash| {
+                let mut submitted = new_registration("0x65f1".to_string());
+                submitted.submit(transaction_hash.to_string());
+                Ok(Some(submitted))
+            });
+
+        let registerer = RegistererImpl::new(
+            MockMyIdentityProvider::new(),
+            Arc::new(registry_mock),
+            Arc::new(MockMyNonceStore::new()),
+            Arc::new(repository_mock),
+            confirmation_tracker::channel().0,
+            Box::new(MockMyEligibilityPolicy::new()),
+        );
+
+        assert!(matches!(
+            registerer.get_registration_status(felt!("0x666")).await,
+            Err(RegistrationError::UnknownTransaction)
+        ));
+        // sent by the service, but not received by the network yet
+        assert_ok_eq!(
+            registerer.get_registration_status(felt!("0x777")).await,
+            TransactionStatus::NotReceived
+        );
     }
 }
@@ -1,15 +1,65 @@
-use crate::infrastructure::starknet_client::StarkNetChain;
+use std::time::Duration;
+
+use reqwest::Url;
+
+use crate::infrastructure::{
+    rule_based_eligibility_policy::EligibilityRules,
+    starknet_client::{SequencerGateway, SignatureVerification, StarkNetChain},
+};
+
+/// OAuth application of an optional identity provider
+pub struct OAuthConfiguration {
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_uri: String,
+    pub access_token_url: String,
+    pub user_api_url: String,
+}
 
 pub struct Configuration {
     pub github_id: String,
     pub github_secret: String,
     pub access_token_url: String,
     pub user_api_url: String,
+    pub applications_api_url: String,
+    pub device_code_url: String,
+    pub authorize_url: String,
+    pub redirect_uri: Option<String>,
+    pub oauth_scopes: Vec<String>,
+    pub oauth_state_capacity: usize,
+    pub oauth_state_ttl: Duration,
+    /// Whether GitHub authorization codes sent without an OAuth state are accepted
+    pub codes_without_oauth_state_allowed: bool,
+    pub identity_session_ttl: Duration,
+    pub github_eligibility: EligibilityRules,
+    pub github_required_memberships: Vec<String>,
+
+    pub gitlab: Option<OAuthConfiguration>,
+    pub discord: Option<OAuthConfiguration>,
 
     pub hex_account_address: String,
     pub hex_private_key: String,
     pub hex_badge_registry_address: String,
+    /// Addresses of the other badge registries served, by registry name
+    pub hex_badge_registry_addresses: Vec<(String, String)>,
     pub chain: StarkNetChain,
+    /// Sequencer gateway, only used when no JSON-RPC node is configured
+    pub gateway: Option<SequencerGateway>,
+    pub rpc_url: Option<String>,
+    pub account_class_hashes: Vec<String>,
+    pub signature_verification: SignatureVerification,
+    pub public_key_cache_capacity: usize,
+    pub public_key_cache_ttl: Duration,
+
+    pub challenge_capacity: usize,
+    pub challenge_ttl: Duration,
+    pub database_path: String,
+    pub max_registration_attempts: u32,
+    pub confirmation_poll_interval: Duration,
+    pub dropped_transaction_polls: u32,
+    pub max_confirmation_watch_time: Duration,
+    pub idempotency_key_ttl: Duration,
+    pub idempotency_lease: Duration,
 }
 
 pub fn load() -> Configuration {
@@ -20,6 +70,48 @@ pub fn load() -> Configuration {
         .unwrap_or_else(|_| "https://github.com/login/oauth/access_token".to_string());
     let user_api_url = std::env::var("GITHUB_USER_API_URL")
         .unwrap_or_else(|_| "https://api.github.com/user".to_string());
+    let applications_api_url = std::env::var("GITHUB_APPLICATIONS_API_URL")
+        .unwrap_or_else(|_| "https://api.github.com/applications".to_string());
+    let device_code_url = std::env::var("GITHUB_DEVICE_CODE_URL")
+        .unwrap_or_else(|_| "https://github.com/login/device/code".to_string());
+    let authorize_url = std::env::var("GITHUB_AUTHORIZE_URL")
+        .unwrap_or_else(|_| "https://github.com/login/oauth/authorize".to_string());
+    let redirect_uri = std::env::var("GITHUB_REDIRECT_URI").ok();
+    let oauth_scopes = load_list("GITHUB_OAUTH_SCOPES");
+    let oauth_state_capacity = std::env::var("OAUTH_STATE_CAPACITY")
+        .unwrap_or_else(|_| "100000".to_string())
+        .parse()
+        .expect("OAUTH_STATE_CAPACITY environment variable must be a number");
+    let oauth_state_ttl = std::env::var("OAUTH_STATE_TTL")
+        .unwrap_or_else(|_| "600".to_string())
+        .parse()
+        .map(Duration::from_secs)
+        .expect("OAUTH_STATE_TTL environment variable must be a number of seconds");
+    let codes_without_oauth_state_allowed = std::env::var("GITHUB_ALLOW_CODES_WITHOUT_OAUTH_STATE")
+        .unwrap_or_else(|_| "false".to_string())
+        .parse()
+        .expect(
+            "GITHUB_ALLOW_CODES_WITHOUT_OAUTH_STATE environment variable must be either 'true' or 'false'",
+        );
+    let identity_session_ttl = std::env::var("IDENTITY_SESSION_TTL")
+        .unwrap_or_else(|_| "900".to_string())
+        .parse()
+        .map(Duration::from_secs)
+        .expect("IDENTITY_SESSION_TTL environment variable must be a number of seconds");
+
+    let github_eligibility = load_eligibility_rules("GITHUB");
+    let github_required_memberships = load_list("GITHUB_REQUIRED_MEMBERSHIPS");
+
+    let gitlab = load_oauth(
+        "GITLAB",
+        "https://gitlab.com/oauth/token",
+        "https://gitlab.com/api/v4/user",
+    );
+    let discord = load_oauth(
+        "DISCORD",
+        "https://discord.com/api/oauth2/token",
+        "https://discord.com/api/users/@me",
+    );
 
     let hex_account_address = std::env::var("STARKNET_ACCOUNT")
         .expect("STARKNET_ACCOUNT environment variable must be set");
@@ -27,20 +119,238 @@ pub fn load() -> Configuration {
         .expect("STARKNET_PRIVATE_KEY environment variable must be set");
     let hex_badge_registry_address = std::env::var("STARKNET_BADGE_REGISTRY_ADDRESS")
         .expect("STARKNET_BADGE_REGISTRY_ADDRESS environment variable must be set");
-    let chain = std::env::var("STARKNET_CHAIN")
-        .expect("STARKNET_CHAIN environment variable must be set to either 'MAINNET' or 'TESTNET'");
-    let chain: StarkNetChain = chain
+    let hex_badge_registry_addresses = load_badge_registries();
+    let chain: StarkNetChain = std::env::var("STARKNET_CHAIN")
+        .expect("STARKNET_CHAIN environment variable must be set")
+        .parse()
+        .unwrap_or_else(|e| panic!("Invalid STARKNET_CHAIN environment variable: {}", e));
+    let rpc_url = std::env::var("STARKNET_RPC_URL").ok();
+    let gateway = rpc_url.is_none().then(|| load_gateway(&chain));
+    let account_class_hashes = load_list("STARKNET_ACCOUNT_CLASS_HASHES");
+    let signature_verification = std::env::var("STARKNET_SIGNATURE_VERIFICATION")
+        .unwrap_or_else(|_| "ON_CHAIN".to_string())
+        .parse()
+        .expect("STARKNET_SIGNATURE_VERIFICATION environment variable must be either 'ON_CHAIN' or 'OFF_CHAIN'");
+    let public_key_cache_capacity = std::env::var("STARKNET_PUBLIC_KEY_CACHE_CAPACITY")
+        .unwrap_or_else(|_| "10000".to_string())
+        .parse()
+        .expect("STARKNET_PUBLIC_KEY_CACHE_CAPACITY environment variable must be a number");
+    let public_key_cache_ttl = std::env::var("STARKNET_PUBLIC_KEY_CACHE_TTL")
+        .unwrap_or_else(|_| "3600".to_string())
+        .parse()
+        .map(Duration::from_secs)
+        .expect("STARKNET_PUBLIC_KEY_CACHE_TTL environment variable must be a number of seconds");
+
+    let challenge_capacity = std::env::var("REGISTRATION_CHALLENGE_CAPACITY")
+        .unwrap_or_else(|_| "100000".to_string())
+        .parse()
+        .expect("REGISTRATION_CHALLENGE_CAPACITY environment variable must be a number");
+    let challenge_ttl = std::env::var("REGISTRATION_CHALLENGE_TTL")
+        .unwrap_or_else(|_| "300".to_string())
+        .parse()
+        .map(Duration::from_secs)
+        .expect("REGISTRATION_CHALLENGE_TTL environment variable must be a number of seconds");
+
+    let database_path = std::env::var("REGISTRATIONS_DATABASE_PATH")
+        .unwrap_or_else(|_| "registrations.db".to_string());
+
+    let max_registration_attempts = std::env::var("REGISTRATION_MAX_ATTEMPTS")
+        .unwrap_or_else(|_| "3".to_string())
+        .parse()
+        .expect("REGISTRATION_MAX_ATTEMPTS environment variable must be a positive number");
+
+    let confirmation_poll_interval = std::env::var("REGISTRATION_POLL_INTERVAL")
+        .unwrap_or_else(|_| "10".to_string())
+        .parse()
+        .map(Duration::from_secs)
+        .expect("REGISTRATION_POLL_INTERVAL environment variable must be a number of seconds");
+
+    let dropped_transaction_polls = std::env::var("REGISTRATION_DROPPED_AFTER_POLLS")
+        .unwrap_or_else(|_| "30".to_string())
         .parse()
-        .expect("STARKNET_CHAIN environment variable must be set to either 'MAINNET' or 'TESTNET'");
+        .expect("REGISTRATION_DROPPED_AFTER_POLLS environment variable must be a positive number");
+
+    let max_confirmation_watch_time = std::env::var("REGISTRATION_MAX_WATCH_TIME")
+        .unwrap_or_else(|_| "3600".to_string())
+        .parse()
+        .map(Duration::from_secs)
+        .expect("REGISTRATION_MAX_WATCH_TIME environment variable must be a number of seconds");
+
+    let idempotency_key_ttl = std::env::var("IDEMPOTENCY_KEY_TTL")
+        .unwrap_or_else(|_| "86400".to_string())
+        .parse()
+        .map(Duration::from_secs)
+        .expect("IDEMPOTENCY_KEY_TTL environment variable must be a number of seconds");
+
+    let idempotency_lease = std::env::var("IDEMPOTENCY_LEASE")
+        .unwrap_or_else(|_| "60".to_string())
+        .parse()
+        .map(Duration::from_secs)
+        .expect("IDEMPOTENCY_LEASE environment variable must be a number of seconds");
 
     Configuration {
         github_id,
         github_secret,
         access_token_url,
         user_api_url,
+        applications_api_url,
+        device_code_url,
+        authorize_url,
+        redirect_uri,
+        oauth_scopes,
+        oauth_state_capacity,
+        oauth_state_ttl,
+        codes_without_oauth_state_allowed,
+        identity_session_ttl,
+        github_eligibility,
+        github_required_memberships,
+        gitlab,
+        discord,
         hex_account_address,
         hex_private_key,
         hex_badge_registry_address,
+        hex_badge_registry_addresses,
         chain,
+        gateway,
+        rpc_url,
+        account_class_hashes,
+        signature_verification,
+        public_key_cache_capacity,
+        public_key_cache_ttl,
+        challenge_capacity,
+        challenge_ttl,
+        database_path,
+        max_registration_attempts,
+        confirmation_poll_interval,
+        dropped_transaction_polls,
+        max_confirmation_watch_time,
+        idempotency_key_ttl,
+        idempotency_lease,
+    }
+}
+
+/// Loads the OAuth application of the `prefix` identity provider, which is disabled if `<prefix>_ID` is not set.
+fn load_oauth(
+    prefix: &str,
+    default_access_token_url: &str,
+    default_user_api_url: &str,
+) -> Option<OAuthConfiguration> {
+    let client_id = std::env::var(format!("{}_ID", prefix)).ok()?;
+    let client_secret = std::env::var(format!("{}_SECRET", prefix))
+        .unwrap_or_else(|_| panic!("{}_SECRET environment variable must be set", prefix));
+    let redirect_uri = std::env::var(format!("{}_REDIRECT_URI", prefix))
+        .unwrap_or_else(|_| panic!("{}_REDIRECT_URI environment variable must be set", prefix));
+    let access_token_url = std::env::var(format!("{}_ACCESS_TOKEN_URL", prefix))
+        .unwrap_or_else(|_| default_access_token_url.to_string());
+    let user_api_url = std::env::var(format!("{}_USER_API_URL", prefix))
+        .unwrap_or_else(|_| default_user_api_url.to_string());
+
+    Some(OAuthConfiguration {
+        client_id,
+        client_secret,
+        redirect_uri,
+        access_token_url,
+        user_api_url,
+    })
+}
+
+/// Loads the eligibility rules of the `prefix` identity provider accounts. Unset rules accept every account.
+fn load_eligibility_rules(prefix: &str) -> EligibilityRules {
+    let min_account_age = std::env::var(format!("{}_MIN_ACCOUNT_AGE", prefix))
+        .unwrap_or_else(|_| "0".to_string())
+        .parse()
+        .map(|days: u64| Duration::from_secs(days * 24 * 60 * 60))
+        .unwrap_or_else(|_| {
+            panic!(
+                "{}_MIN_ACCOUNT_AGE environment variable must be a number of days",
+                prefix
+            )
+        });
+    let min_public_repos = std::env::var(format!("{}_MIN_PUBLIC_REPOS", prefix))
+        .unwrap_or_else(|_| "0".to_string())
+        .parse()
+        .unwrap_or_else(|_| {
+            panic!(
+                "{}_MIN_PUBLIC_REPOS environment variable must be a positive number",
+                prefix
+            )
+        });
+    let min_followers = std::env::var(format!("{}_MIN_FOLLOWERS", prefix))
+        .unwrap_or_else(|_| "0".to_string())
+        .parse()
+        .unwrap_or_else(|_| {
+            panic!(
+                "{}_MIN_FOLLOWERS environment variable must be a positive number",
+                prefix
+            )
+        });
+
+    EligibilityRules {
+        min_account_age,
+        min_public_repos,
+        min_followers,
+        allowed_accounts: load_list(&format!("{}_ALLOWED_ACCOUNTS", prefix)),
+        denied_accounts: load_list(&format!("{}_DENIED_ACCOUNTS", prefix)),
     }
 }
+
+/// Loads the sequencer gateway of `chain`. The public gateway of known chains is used unless overridden,
+/// custom chains must set both URLs.
+fn load_gateway(chain: &StarkNetChain) -> SequencerGateway {
+    let default_gateway = chain.default_gateway();
+    let load_url = |name: &str, default_url: Option<&Url>| {
+        std::env::var(name)
+            .ok()
+            .map(|url| {
+                Url::parse(&url).unwrap_or_else(|e| {
+                    panic!("{} environment variable must be a URL: {}", name, e)
+                })
+            })
+            .or_else(|| default_url.cloned())
+            .unwrap_or_else(|| {
+                panic!(
+                    "{} environment variable must be set for custom StarkNet chains",
+                    name
+                )
+            })
+    };
+
+    SequencerGateway {
+        gateway_url: load_url(
+            "STARKNET_GATEWAY_URL",
+            default_gateway.as_ref().map(|gateway| &gateway.gateway_url),
+        ),
+        feeder_gateway_url: load_url(
+            "STARKNET_FEEDER_GATEWAY_URL",
+            default_gateway
+                .as_ref()
+                .map(|gateway| &gateway.feeder_gateway_url),
+        ),
+    }
+}
+
+/// Loads the `name=address` pairs of STARKNET_BADGE_REGISTRIES.
+fn load_badge_registries() -> Vec<(String, String)> {
+    load_list("STARKNET_BADGE_REGISTRIES")
+        .iter()
+        .map(|registry| match registry.split_once('=') {
+            Some((name, address)) if !name.trim().is_empty() => {
+                (name.trim().to_string(), address.trim().to_string())
+            }
+            _ => panic!(
+                "STARKNET_BADGE_REGISTRIES environment variable must be a list of name=address pairs"
+            ),
+        })
+        .collect()
+}
+
+/// Loads a comma separated list, empty if `name` is not set.
+fn load_list(name: &str) -> Vec<String> {
+    std::env::var(name)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
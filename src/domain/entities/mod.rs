@@ -0,0 +1,75 @@
+use std::time::SystemTime;
+
+use crate::domain::value_objects::{Identity, IdentityProfile};
+
+pub type RegistrationId = i64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RegistrationStatus {
+    /// The registration request has been received and is being processed
+    Started,
+    /// The registration transaction has been sent to the registry
+    Submitted,
+    /// The registration failed before any transaction could be sent
+    Failed,
+    /// The registration transaction has been accepted on StarkNet
+    Accepted,
+    /// The registration transaction has been rejected, and will not be retried anymore
+    Rejected,
+    /// The identity was already registered with the same account, no transaction has been sent
+    AlreadyRegistered,
+}
+
+/// A registration attempt, as it is recorded by the service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Registration {
+    pub id: RegistrationId,
+    pub status: RegistrationStatus,
+    /// Only known once the user has been identified by the identity provider
+    pub identity: Option<Identity>,
+    /// Profile of the user when identified, as given by the identity provider
+    pub profile: Option<IdentityProfile>,
+    pub account_address: String,
+    pub transaction_hash: Option<String>,
+    pub error_kind: Option<String>,
+    pub created_at: SystemTime,
+    pub updated_at: SystemTime,
+}
+
+impl Registration {
+    pub fn submit(&mut self, transaction_hash: String) {
+        self.status = RegistrationStatus::Submitted;
+        self.transaction_hash = Some(transaction_hash);
+        self.updated_at = SystemTime::now();
+    }
+
+    pub fn fail(&mut self, error_kind: &str) {
+        self.status = RegistrationStatus::Failed;
+        self.error_kind = Some(error_kind.to_string());
+        self.updated_at = SystemTime::now();
+    }
+
+    pub fn accept(&mut self) {
+        self.status = RegistrationStatus::Accepted;
+        self.updated_at = SystemTime::now();
+    }
+
+    pub fn already_registered(&mut self, transaction_hash: Option<String>) {
+        self.status = RegistrationStatus::AlreadyRegistered;
+        self.transaction_hash = transaction_hash;
+        self.updated_at = SystemTime::now();
+    }
+
+    pub fn reject(&mut self) {
+        self.status = RegistrationStatus::Rejected;
+        self.error_kind = Some("transaction_rejected".to_string());
+        self.updated_at = SystemTime::now();
+    }
+
+    /// Gives up on a transaction that was neither accepted nor rejected in time.
+    pub fn time_out(&mut self) {
+        self.status = RegistrationStatus::Rejected;
+        self.error_kind = Some("transaction_timed_out".to_string());
+        self.updated_at = SystemTime::now();
+    }
+}
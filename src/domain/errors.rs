@@ -10,34 +10,175 @@ pub enum RegistrationError {
     Identification(#[source] IdentificationError),
     #[error("Signature error")]
     Signature(#[source] SignatureError),
+    #[error("Challenge error")]
+    Challenge(#[source] ChallengeError),
+    #[error("OAuth state error")]
+    State(#[source] AuthorizationStateError),
+    #[error("Identity session error")]
+    Session(#[source] IdentitySessionError),
+    #[error("Repository error")]
+    Repository(#[source] RepositoryError),
+    #[error("Identity already registered with another account")]
+    AlreadyRegistered,
+    #[error("Identity not registered with this account")]
+    NotRegistered,
+    #[error("Account is too recent")]
+    AccountTooRecent,
+    #[error("Account does not have enough public repositories")]
+    NotEnoughRepositories,
+    #[error("Account does not have enough followers")]
+    NotEnoughFollowers,
+    #[error("Account is suspended")]
+    AccountSuspended,
+    #[error("Account is denied")]
+    AccountDenied,
+    #[error("Account is not a member of the required organizations or teams")]
+    NotAMember,
+    #[error("Transaction is unknown")]
+    UnknownTransaction,
+}
+
+impl RegistrationError {
+    /// Short and stable name of the error, suitable for storage and metrics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RegistrationError::Registry(_) => "registry",
+            RegistrationError::Authentication(_) => "authentication",
+            RegistrationError::Identification(_) => "identification",
+            RegistrationError::Signature(_) => "signature",
+            RegistrationError::Challenge(_) => "challenge",
+            RegistrationError::State(_) => "state",
+            RegistrationError::Session(_) => "session",
+            RegistrationError::Repository(_) => "repository",
+            RegistrationError::AlreadyRegistered => "already_registered",
+            RegistrationError::NotRegistered => "not_registered",
+            RegistrationError::AccountTooRecent => "account_too_recent",
+            RegistrationError::NotEnoughRepositories => "not_enough_repositories",
+            RegistrationError::NotEnoughFollowers => "not_enough_followers",
+            RegistrationError::AccountSuspended => "account_suspended",
+            RegistrationError::AccountDenied => "account_denied",
+            RegistrationError::NotAMember => "not_a_member",
+            RegistrationError::UnknownTransaction => "unknown_transaction",
+        }
+    }
+}
+
+impl From<EligibilityError> for RegistrationError {
+    fn from(error: EligibilityError) -> Self {
+        match error {
+            EligibilityError::AccountTooRecent => RegistrationError::AccountTooRecent,
+            EligibilityError::NotEnoughRepositories => RegistrationError::NotEnoughRepositories,
+            EligibilityError::NotEnoughFollowers => RegistrationError::NotEnoughFollowers,
+            EligibilityError::AccountSuspended => RegistrationError::AccountSuspended,
+            EligibilityError::AccountDenied => RegistrationError::AccountDenied,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ChallengeError {
+    #[error("Unknown or already used nonce")]
+    Unknown,
+    #[error("Expired nonce")]
+    Expired,
+    #[error("Nonce issued for another account")]
+    OtherAccount,
+}
+
+#[derive(Debug, Error)]
+pub enum AuthorizationStateError {
+    #[error("Missing OAuth state")]
+    Missing,
+    #[error("Unknown or already used OAuth state")]
+    Unknown,
+    #[error("Expired OAuth state")]
+    Expired,
+}
+
+#[derive(Debug, Error)]
+pub enum IdentitySessionError {
+    #[error("Identity sessions are not enabled")]
+    Unsupported,
+    #[error("Unknown or already used identity session")]
+    Unknown,
+    #[error("Expired identity session")]
+    Expired,
 }
 
 #[derive(Debug, Error)]
 pub enum AuthenticationError {
     #[error("HTTP request error")]
-    Http(#[source] Box<dyn std::error::Error>),
+    Http(#[source] Box<dyn std::error::Error + Send + Sync>),
     #[error("(de)serialization error")]
-    Serde(#[source] Box<dyn std::error::Error>),
+    Serde(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Rejected by the identity provider: {0}")]
+    Rejected(String),
+}
+
+#[derive(Debug, Error)]
+pub enum DeviceAuthorizationError {
+    #[error("Device code expired")]
+    Expired,
+    #[error("Device authorization denied by the user")]
+    Denied,
+    #[error("Authentication error")]
+    Authentication(#[source] AuthenticationError),
 }
 
 #[derive(Debug, Error)]
 pub enum IdentificationError {
+    #[error("Access token is invalid, expired or revoked")]
+    Unauthorized,
     #[error("HTTP request error")]
-    Http(#[source] Box<dyn std::error::Error>),
+    Http(#[source] Box<dyn std::error::Error + Send + Sync>),
     #[error("(de)serialization error")]
-    Serde(#[source] Box<dyn std::error::Error>),
+    Serde(#[source] Box<dyn std::error::Error + Send + Sync>),
 }
 
 #[derive(Debug, Error)]
 pub enum SignatureError {
     #[error("Invalid signature")]
-    InvalidSignature(#[source] Box<dyn std::error::Error>),
+    InvalidSignature(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Account is not deployed")]
+    AccountNotDeployed,
+    #[error("Contract class {0} is not a supported account implementation")]
+    UnsupportedAccount(String),
+    #[error("Failed to verify the signature with the StarkNet provider")]
+    Provider(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Signed hash does not match the registration message")]
+    UnexpectedHash,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum EligibilityError {
+    #[error("Account is younger than the minimum account age")]
+    AccountTooRecent,
+    #[error("Account has less public repositories than required")]
+    NotEnoughRepositories,
+    #[error("Account has less followers than required")]
+    NotEnoughFollowers,
+    #[error("Account is suspended by the identity provider")]
+    AccountSuspended,
+    #[error("Account is in the deny list")]
+    AccountDenied,
 }
 
 #[derive(Debug, Error)]
 pub enum RegistryError {
     #[error("Nonce error")]
-    Nonce(#[source] Box<dyn std::error::Error>),
+    Nonce(#[source] Box<dyn std::error::Error + Send + Sync>),
     #[error("Transaction failed")]
-    Transaction(#[source] Box<dyn std::error::Error>),
+    Transaction(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Transaction status unavailable")]
+    Status(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Registry query failed")]
+    Query(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+#[derive(Debug, Error)]
+pub enum RepositoryError {
+    #[error("Storage error")]
+    Storage(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Corrupted record")]
+    Corrupted(String),
 }
@@ -0,0 +1,11 @@
+use crate::domain::{errors::AuthorizationStateError, value_objects::AuthorizationState};
+
+#[async_trait]
+pub trait AuthorizationStateStore: Send + Sync {
+    /// Issues a new OAuth state, along with a PKCE code verifier, valid until it expires.
+    async fn issue(&self) -> AuthorizationState;
+
+    /// Consumes a previously issued OAuth state, and returns it with its code verifier.
+    /// A state can only be consumed once.
+    async fn consume(&self, state: &str) -> Result<AuthorizationState, AuthorizationStateError>;
+}
@@ -0,0 +1,21 @@
+use crate::domain::{
+    errors::{AuthenticationError, DeviceAuthorizationError},
+    value_objects::{DeviceAuthorization, DeviceAuthorizationStatus, TokenGrant},
+};
+
+/// OAuth device authorization flow, for clients that cannot receive a browser redirect.
+#[async_trait]
+pub trait DeviceAuthorizationProvider: Send + Sync {
+    async fn start_device_authorization(&self) -> Result<DeviceAuthorization, AuthenticationError>;
+
+    async fn poll_device_authorization(
+        &self,
+        device_code: &str,
+    ) -> Result<DeviceAuthorizationStatus, DeviceAuthorizationError>;
+
+    /// Exchanges the refresh token of an expiring access token for a new grant.
+    async fn refresh_access_token(
+        &self,
+        refresh_token: &str,
+    ) -> Result<TokenGrant, AuthenticationError>;
+}
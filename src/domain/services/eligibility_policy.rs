@@ -0,0 +1,10 @@
+use crate::domain::{
+    errors::EligibilityError,
+    value_objects::{Identity, IdentityProfile},
+};
+
+/// Decides whether an identified user may be registered, before any transaction is sent.
+pub trait EligibilityPolicy: Send + Sync {
+    fn check(&self, identity: &Identity, profile: &IdentityProfile)
+        -> Result<(), EligibilityError>;
+}
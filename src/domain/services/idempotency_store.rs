@@ -0,0 +1,14 @@
+use crate::domain::value_objects::IdempotentRequest;
+
+#[async_trait]
+pub trait IdempotencyStore<T>: Send + Sync
+where
+    T: Clone + Send + Sync,
+{
+    /// Starts the request identified by `key`, unless a request with the same key has already been received.
+    /// `fingerprint` identifies the content of the request, a key cannot be reused for another content.
+    async fn begin(&self, key: &str, fingerprint: &str) -> IdempotentRequest<T>;
+
+    /// Records the response of the request started with `key`, so that it can be replayed on retries.
+    async fn complete(&self, key: &str, fingerprint: &str, response: T);
+}
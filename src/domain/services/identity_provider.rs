@@ -1,18 +1,45 @@
 use crate::domain::{
     errors::AuthenticationError,
     errors::IdentificationError,
-    value_objects::{AccessToken, Identity},
+    value_objects::{AccessToken, Identity, IdentityProfile},
 };
 
 #[async_trait]
 pub trait IdentityProvider: Send + Sync {
+    /// Exchanges `authorization_code` for an access token. `code_verifier` is the PKCE code
+    /// verifier of the authorization request, if it was sent with a code challenge.
     async fn new_access_token(
         &self,
         authorization_code: &str,
+        code_verifier: Option<String>,
     ) -> Result<AccessToken, AuthenticationError>;
 
-    async fn get_user_id(
+    /// URL of the authorization page the user is redirected to, for the given OAuth `state` and
+    /// PKCE `code_challenge`. Providers without server-side authorization requests return none.
+    fn authorization_url(&self, _state: &str, _code_challenge: &str) -> Option<String> {
+        None
+    }
+
+    async fn get_user(
         &self,
         access_token: &AccessToken,
-    ) -> Result<Identity, IdentificationError>;
+    ) -> Result<(Identity, IdentityProfile), IdentificationError>;
+
+    /// Organizations (`org`) and teams (`org/team`) the user belongs to.
+    /// Providers without such a notion return none.
+    async fn get_memberships(
+        &self,
+        _access_token: &AccessToken,
+    ) -> Result<Vec<String>, IdentificationError> {
+        Ok(Vec::new())
+    }
+
+    /// Revokes `access_token`, which must not be used anymore.
+    /// Providers that cannot revoke tokens let them expire.
+    async fn revoke_access_token(
+        &self,
+        _access_token: &AccessToken,
+    ) -> Result<(), AuthenticationError> {
+        Ok(())
+    }
 }
@@ -0,0 +1,16 @@
+use crate::domain::{
+    errors::IdentitySessionError,
+    value_objects::{Identity, IdentityProfile, IdentitySession},
+};
+
+#[async_trait]
+pub trait IdentitySessionStore: Send + Sync {
+    /// Opens a session for the identified user, valid until it expires.
+    async fn open(&self, identity: Identity, profile: IdentityProfile) -> IdentitySession;
+
+    /// Returns a previously opened session, which is left open.
+    async fn get(&self, id: &str) -> Result<IdentitySession, IdentitySessionError>;
+
+    /// Closes a previously opened session, and returns it. A session can only be closed once.
+    async fn close(&self, id: &str) -> Result<IdentitySession, IdentitySessionError>;
+}
@@ -1,2 +1,9 @@
+pub mod authorization_state_store;
+pub mod device_authorization_provider;
+pub mod eligibility_policy;
+pub mod idempotency_store;
 pub mod identity_provider;
+pub mod identity_session_store;
+pub mod nonce_store;
 pub mod onchain_registry;
+pub mod registration_repository;
@@ -0,0 +1,13 @@
+use crate::domain::{
+    errors::ChallengeError,
+    value_objects::{Challenge, Nonce},
+};
+
+#[async_trait]
+pub trait NonceStore: Send + Sync {
+    /// Issues a new one-time nonce for `account_address`, valid until the returned challenge expires.
+    async fn issue(&self, account_address: &str) -> Challenge;
+
+    /// Consumes a nonce previously issued for `account_address`. A nonce can only be consumed once.
+    async fn consume(&self, nonce: &Nonce, account_address: &str) -> Result<(), ChallengeError>;
+}
@@ -1,26 +1,69 @@
-use std::str::FromStr;
+use std::{fmt::LowerHex, str::FromStr};
 
 use crate::domain::{
     errors::{RegistryError, SignatureError},
-    value_objects::Identity,
+    value_objects::{Identity, Nonce, SignedOperation, TransactionStatus},
 };
 
 #[async_trait]
 pub trait OnChainRegistry: Send + Sync {
     type SignedData: Clone + Send + Sync;
-    type AccountAddress: Clone + Send + Sync;
-    type TransactionHash: FromStr + Clone + Send + Sync;
-    type ContributorId: From<Identity> + Send + Sync;
+    type AccountAddress: LowerHex + FromStr + PartialEq + Clone + Send + Sync;
+    type TransactionHash: LowerHex + FromStr + Clone + Send + Sync;
+    type ContributorId: From<Identity> + Clone + Send + Sync;
 
+    /// Checks that `signed_data` holds a valid signature, issued by `account_address`,
+    /// of the `operation` message binding this account to `user_id` for the given `nonce`.
     async fn check_signature(
         &self,
         signed_data: Self::SignedData,
         account_address: Self::AccountAddress,
+        user_id: Self::ContributorId,
+        nonce: Nonce,
+        operation: SignedOperation,
+    ) -> Result<(), SignatureError>;
+
+    /// Checks that `signed_data` holds a valid signature, issued by `signer_address`, of the
+    /// message moving `user_id` from `old_account_address` to `new_account_address` for the given `nonce`.
+    async fn check_migration_signature(
+        &self,
+        signed_data: Self::SignedData,
+        signer_address: Self::AccountAddress,
+        old_account_address: Self::AccountAddress,
+        new_account_address: Self::AccountAddress,
+        user_id: Self::ContributorId,
+        nonce: Nonce,
     ) -> Result<(), SignatureError>;
 
+    /// Returns the account `user_id` is currently registered with, if any.
+    async fn get_registered_account(
+        &self,
+        user_id: Self::ContributorId,
+    ) -> Result<Option<Self::AccountAddress>, RegistryError>;
+
     async fn register_contributor(
         &self,
         user_account_address: Self::AccountAddress,
         user_id: Self::ContributorId,
     ) -> Result<Self::TransactionHash, RegistryError>;
+
+    async fn unregister_contributor(
+        &self,
+        user_account_address: Self::AccountAddress,
+        user_id: Self::ContributorId,
+    ) -> Result<Self::TransactionHash, RegistryError>;
+
+    /// Moves the registration of `user_id` from `old_account_address` to `new_account_address`,
+    /// in a single transaction.
+    async fn migrate_contributor(
+        &self,
+        old_account_address: Self::AccountAddress,
+        new_account_address: Self::AccountAddress,
+        user_id: Self::ContributorId,
+    ) -> Result<Self::TransactionHash, RegistryError>;
+
+    async fn get_transaction_status(
+        &self,
+        transaction_hash: Self::TransactionHash,
+    ) -> Result<TransactionStatus, RegistryError>;
 }
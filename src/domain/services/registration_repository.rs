@@ -0,0 +1,29 @@
+use crate::domain::{
+    entities::{Registration, RegistrationStatus},
+    errors::RepositoryError,
+    value_objects::Identity,
+};
+
+#[async_trait]
+pub trait RegistrationRepository: Send + Sync {
+    /// Records a new registration attempt for `account_address`, in the `Started` status.
+    async fn create(&self, account_address: String) -> Result<Registration, RepositoryError>;
+
+    async fn save(&self, registration: &Registration) -> Result<(), RepositoryError>;
+
+    async fn find_by_identity(
+        &self,
+        identity: &Identity,
+    ) -> Result<Vec<Registration>, RepositoryError>;
+
+    async fn find_by_transaction_hash(
+        &self,
+        transaction_hash: &str,
+    ) -> Result<Option<Registration>, RepositoryError>;
+
+    /// Returns the registrations currently in `status`, oldest first.
+    async fn find_by_status(
+        &self,
+        status: RegistrationStatus,
+    ) -> Result<Vec<Registration>, RepositoryError>;
+}
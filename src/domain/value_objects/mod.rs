@@ -1,4 +1,11 @@
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    num::ParseIntError,
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
+
+use sha2::{Digest, Sha256};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct AccessToken(String);
@@ -15,6 +22,51 @@ impl From<String> for AccessToken {
     }
 }
 
+/// Access token granted by the identity provider, along with its refresh token for expiring tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenGrant {
+    pub access_token: AccessToken,
+    /// Unset for tokens that never expire
+    pub expires_in: Option<Duration>,
+    pub refresh_token: Option<String>,
+    pub refresh_token_expires_in: Option<Duration>,
+}
+
+/// Proof of identity sent by the user.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Authorization {
+    /// OAuth authorization code, exchanged for an access token, along with the OAuth state of the
+    /// authorization request when it was issued by `GET /oauth/<provider>/authorize`
+    Code { code: String, state: Option<String> },
+    /// Access token obtained by the user, e.g. through the device authorization flow
+    AccessToken(AccessToken),
+    /// Identity session opened once the user was identified by `GET /oauth/<provider>/callback`
+    Session(String),
+}
+
+/// Device authorization started with the identity provider.
+/// The user must enter `user_code` at `verification_uri` before it expires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceAuthorization {
+    pub device_code: String,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub expires_in: Duration,
+    /// Minimum interval between two polls
+    pub interval: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceAuthorizationStatus {
+    /// The user has not entered the user code yet
+    Pending,
+    /// Polls are too frequent, the interval must be increased
+    SlowDown {
+        interval: Duration,
+    },
+    Authorized(TokenGrant),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct GitHubId(pub u64);
 
@@ -24,7 +76,167 @@ impl From<u64> for GitHubId {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GitLabId(pub u64);
+
+impl From<u64> for GitLabId {
+    fn from(value: u64) -> Self {
+        GitLabId(value)
+    }
+}
+
+/// Discord user id (snowflake)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DiscordId(pub u64);
+
+impl From<u64> for DiscordId {
+    fn from(value: u64) -> Self {
+        DiscordId(value)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Identity {
     GitHubId(GitHubId),
+    GitLabId(GitLabId),
+    DiscordId(DiscordId),
+}
+
+impl Identity {
+    /// Short and stable name of the identity provider, suitable for storage and entry point names.
+    pub fn provider(&self) -> &'static str {
+        match self {
+            Identity::GitHubId(_) => "github",
+            Identity::GitLabId(_) => "gitlab",
+            Identity::DiscordId(_) => "discord",
+        }
+    }
+
+    /// Id of the user in the identity provider.
+    pub fn user_id(&self) -> u64 {
+        match self {
+            Identity::GitHubId(github_id) => github_id.0,
+            Identity::GitLabId(gitlab_id) => gitlab_id.0,
+            Identity::DiscordId(discord_id) => discord_id.0,
+        }
+    }
+}
+
+/// Public profile of a user, as given by the identity provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentityProfile {
+    /// Handle of the user, e.g. the GitHub login
+    pub login: String,
+    pub name: Option<String>,
+    pub avatar_url: Option<String>,
+    /// Creation date of the account, in RFC 3339 format
+    pub created_at: Option<String>,
+    /// Activity of the account, when the identity provider gives it
+    pub public_repos: Option<u64>,
+    pub followers: Option<u64>,
+    pub suspended: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Nonce(pub u128);
+
+impl From<u128> for Nonce {
+    fn from(value: u128) -> Self {
+        Nonce(value)
+    }
+}
+
+impl Display for Nonce {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:#x}", self.0)
+    }
+}
+
+impl FromStr for Nonce {
+    type Err = ParseIntError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let digits = input.strip_prefix("0x").unwrap_or(input);
+        u128::from_str_radix(digits, 16).map(Nonce)
+    }
+}
+
+/// Registry operation a user signs a message for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignedOperation {
+    Registration,
+    Unregistration,
+    /// Moving a registration from an account to another one
+    Migration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Challenge {
+    pub nonce: Nonce,
+    pub expires_at: SystemTime,
+}
+
+/// OAuth state of an authorization request, bound to its PKCE code verifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorizationState {
+    pub state: String,
+    pub code_verifier: String,
+    pub expires_at: SystemTime,
+}
+
+impl AuthorizationState {
+    /// PKCE `S256` code challenge of the code verifier.
+    pub fn code_challenge(&self) -> String {
+        base64::encode_config(
+            Sha256::digest(self.code_verifier.as_bytes()),
+            base64::URL_SAFE_NO_PAD,
+        )
+    }
+}
+
+/// Identity of a user that has not proven the ownership of an account yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdentitySession {
+    pub id: String,
+    pub identity: Identity,
+    pub profile: IdentityProfile,
+    pub expires_at: SystemTime,
+}
+
+/// State of a request sent with an idempotency key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdempotentRequest<T> {
+    /// First request received with this key
+    New,
+    /// A request with the same key is still being processed
+    InProgress,
+    /// A request with the same key has already been processed, with the given response
+    Completed(T),
+    /// The key has already been used by a different request
+    Mismatch,
+}
+
+/// Normalized status of a registry transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionStatus {
+    /// The transaction is unknown to the network: it has not been received yet, has been dropped,
+    /// or has never been sent
+    NotReceived,
+    Pending,
+    AcceptedOnL2,
+    AcceptedOnL1,
+    Rejected {
+        reason: Option<String>,
+    },
+}
+
+/// Outcome of a successful registration request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistrationOutcome<H> {
+    /// A registration transaction has been sent to the registry
+    Submitted(H),
+    /// The identity was already registered with the same account, or its registration transaction is
+    /// still pending, no transaction has been sent.
+    /// The hash of the original transaction is only known if it has been sent by this service.
+    AlreadyRegistered { transaction_hash: Option<H> },
 }
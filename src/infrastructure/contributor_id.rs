@@ -0,0 +1,156 @@
+//! On-chain encoding of contributor identities.
+//!
+//! A contributor id is a single field element laid out, from the least significant byte, as:
+//!
+//! - bytes 0 to 7: the user id given by the identity provider
+//! - byte 8: the identity provider tag
+//! - byte 9: the encoding version
+//! - remaining bytes: zero
+//!
+//! Version 0 is the legacy encoding, where the raw GitHub id was written in the field element.
+//! It is kept for GitHub ids so that contributors registered before namespacing keep their id.
+//! Other providers use version 1, which namespaces ids with the provider tag.
+
+use starknet::core::types::FieldElement;
+use thiserror::Error;
+
+use crate::domain::value_objects::Identity;
+
+const LEGACY_VERSION: u8 = 0;
+const NAMESPACED_VERSION: u8 = 1;
+
+const GITHUB_TAG: u8 = 0;
+const GITLAB_TAG: u8 = 1;
+const DISCORD_TAG: u8 = 2;
+
+const ID_LENGTH: usize = 8;
+const TAG_INDEX: usize = 32 - ID_LENGTH - 1;
+const VERSION_INDEX: usize = TAG_INDEX - 1;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ContributorIdError {
+    #[error("Contributor id has non-zero reserved bits")]
+    ReservedBits,
+    #[error("Unknown contributor id namespace: version {version}, provider {provider}")]
+    UnknownNamespace { version: u8, provider: u8 },
+}
+
+impl From<&Identity> for FieldElement {
+    fn from(identity: &Identity) -> Self {
+        let (version, tag, id) = match identity {
+            Identity::GitHubId(github_id) => (LEGACY_VERSION, GITHUB_TAG, github_id.0),
+            Identity::GitLabId(gitlab_id) => (NAMESPACED_VERSION, GITLAB_TAG, gitlab_id.0),
+            Identity::DiscordId(discord_id) => (NAMESPACED_VERSION, DISCORD_TAG, discord_id.0),
+        };
+
+        let mut bytes = [0u8; 32];
+        bytes[VERSION_INDEX] = version;
+        bytes[TAG_INDEX] = tag;
+        bytes[32 - ID_LENGTH..].copy_from_slice(&id.to_be_bytes());
+        // 80 bits always fit in a field element
+        FieldElement::from_bytes_be(&bytes).unwrap()
+    }
+}
+
+impl From<Identity> for FieldElement {
+    fn from(identity: Identity) -> Self {
+        FieldElement::from(&identity)
+    }
+}
+
+impl TryFrom<FieldElement> for Identity {
+    type Error = ContributorIdError;
+
+    fn try_from(contributor_id: FieldElement) -> Result<Self, Self::Error> {
+        let bytes = contributor_id.to_bytes_be();
+        if bytes[..VERSION_INDEX].iter().any(|byte| *byte != 0) {
+            return Err(ContributorIdError::ReservedBits);
+        }
+
+        let mut id = [0u8; ID_LENGTH];
+        id.copy_from_slice(&bytes[32 - ID_LENGTH..]);
+        let id = u64::from_be_bytes(id);
+
+        match (bytes[VERSION_INDEX], bytes[TAG_INDEX]) {
+            (LEGACY_VERSION, GITHUB_TAG) => Ok(Identity::GitHubId(id.into())),
+            (NAMESPACED_VERSION, GITLAB_TAG) => Ok(Identity::GitLabId(id.into())),
+            (NAMESPACED_VERSION, DISCORD_TAG) => Ok(Identity::DiscordId(id.into())),
+            (version, provider) => Err(ContributorIdError::UnknownNamespace { version, provider }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use starknet::core::types::FieldElement;
+
+    use super::ContributorIdError;
+    use crate::domain::value_objects::Identity;
+
+    fn identity() -> impl Strategy<Value = Identity> {
+        prop_oneof![
+            any::<u64>().prop_map(|id| Identity::GitHubId(id.into())),
+            any::<u64>().prop_map(|id| Identity::GitLabId(id.into())),
+            any::<u64>().prop_map(|id| Identity::DiscordId(id.into())),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn contributor_id_decodes_to_the_encoded_identity(identity in identity()) {
+            let contributor_id = FieldElement::from(&identity);
+
+            prop_assert_eq!(Identity::try_from(contributor_id), Ok(identity));
+        }
+
+        #[test]
+        fn contributor_id_is_injective(first in identity(), second in identity()) {
+            prop_assume!(first != second);
+
+            prop_assert_ne!(FieldElement::from(&first), FieldElement::from(&second));
+        }
+
+        #[test]
+        fn same_user_id_of_different_providers_do_not_collide(id in any::<u64>()) {
+            let github = FieldElement::from(Identity::GitHubId(id.into()));
+            let gitlab = FieldElement::from(Identity::GitLabId(id.into()));
+            let discord = FieldElement::from(Identity::DiscordId(id.into()));
+
+            prop_assert_ne!(github, gitlab);
+            prop_assert_ne!(github, discord);
+            prop_assert_ne!(gitlab, discord);
+        }
+    }
+
+    #[test]
+    fn github_ids_keep_their_legacy_encoding() {
+        assert_eq!(
+            FieldElement::from(Identity::GitHubId(42.into())),
+            FieldElement::from(42u64)
+        );
+    }
+
+    #[test]
+    fn unknown_namespace_is_rejected() {
+        let contributor_id = FieldElement::from_hex_be("0x01000000000000002a").unwrap();
+
+        assert_eq!(
+            Identity::try_from(contributor_id),
+            Err(ContributorIdError::UnknownNamespace {
+                version: 0,
+                provider: 1
+            })
+        );
+    }
+
+    #[test]
+    fn reserved_bits_are_rejected() {
+        let contributor_id = FieldElement::from_hex_be("0x1000000000000000000002a").unwrap();
+
+        assert_eq!(
+            Identity::try_from(contributor_id),
+            Err(ContributorIdError::ReservedBits)
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use rocket::serde::{Deserialize, Serialize};
+
+use crate::domain::{
+    errors::AuthenticationError,
+    errors::IdentificationError,
+    services::identity_provider::IdentityProvider,
+    value_objects::{AccessToken, Identity, IdentityProfile},
+};
+
+const CDN_URL: &str = "https://cdn.discordapp.com";
+
+#[derive(Clone)]
+pub struct DiscordClient {
+    http_client: reqwest::Client,
+
+    access_token_url: String,
+    user_api_url: String,
+
+    discord_id: String,
+    discord_secret: String,
+    redirect_uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct AccessTokenRequestBody<'r> {
+    client_id: &'r str,
+    client_secret: &'r str,
+    code: &'r str,
+    grant_type: &'r str,
+    redirect_uri: &'r str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code_verifier: Option<&'r str>,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct AccessTokenResponseBody {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct UserResponseBody {
+    /// Discord ids are 64 bits snowflakes, serialized as strings
+    id: String,
+    username: String,
+    global_name: Option<String>,
+    /// Hash of the avatar image, served by the Discord CDN
+    avatar: Option<String>,
+}
+
+impl DiscordClient {
+    pub fn new(
+        discord_id: String,
+        discord_secret: String,
+        redirect_uri: String,
+        access_token_url: String,
+        user_api_url: String,
+    ) -> Self {
+        DiscordClient {
+            http_client: reqwest::Client::new(),
+            access_token_url,
+            user_api_url,
+            discord_id,
+            discord_secret,
+            redirect_uri,
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl IdentityProvider for DiscordClient {
+    async fn new_access_token(
+        &self,
+        authorization_code: &str,
+        code_verifier: Option<String>,
+    ) -> Result<AccessToken, AuthenticationError> {
+        let request_body = AccessTokenRequestBody {
+            client_id: &self.discord_id,
+            client_secret: &self.discord_secret,
+            code: authorization_code,
+            grant_type: "authorization_code",
+            redirect_uri: &self.redirect_uri,
+            code_verifier: code_verifier.as_deref(),
+        };
+
+        // Discord only accepts form encoded token requests
+        let response = self
+            .http_client
+            .post(&self.access_token_url)
+            .form(&request_body)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send()
+            .await
+            .map_err(|e| AuthenticationError::Http(Box::new(e)))?
+            .error_for_status()
+            .map_err(|e| AuthenticationError::Http(Box::new(e)))?;
+
+        let response = response
+            .json::<AccessTokenResponseBody>()
+            .await
+            .map_err(|e| AuthenticationError::Serde(Box::new(e)))?;
+
+        Ok(AccessToken::from(response.access_token))
+    }
+
+    async fn get_user(
+        &self,
+        access_token: &AccessToken,
+    ) -> Result<(Identity, IdentityProfile), IdentificationError> {
+        let response = self
+            .http_client
+            .get(&self.user_api_url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| IdentificationError::Http(Box::new(e)))?
+            .error_for_status()
+            .map_err(|e| IdentificationError::Http(Box::new(e)))?;
+
+        let response = response
+            .json::<UserResponseBody>()
+            .await
+            .map_err(|e| IdentificationError::Serde(Box::new(e)))?;
+
+        let id = response
+            .id
+            .parse::<u64>()
+            .map_err(|e| IdentificationError::Serde(Box::new(e)))?;
+
+        let avatar_url = response
+            .avatar
+            .map(|avatar| format!("{}/avatars/{}/{}.png", CDN_URL, response.id, avatar));
+
+        Ok((
+            Identity::DiscordId(id.into()),
+            IdentityProfile {
+                login: response.username,
+                name: response.global_name,
+                avatar_url,
+                created_at: None,
+                public_repos: None,
+                followers: None,
+                suspended: false,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::domain::{
+        services::identity_provider::IdentityProvider,
+        value_objects::{AccessToken, Identity, IdentityProfile},
+    };
+
+    use super::DiscordClient;
+    use claim::*;
+    use httpmock::prelude::*;
+    use rocket::{serde::json::serde_json, tokio};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn new_access_token() {
+        let server = MockServer::start();
+
+        let discord_client = DiscordClient::new(
+            "foo-discord-id".into(),
+            "foo-discord-secret".into(),
+            "https://foo.test/callback".into(),
+            server.url("/api/oauth2/token"),
+            "".into(),
+        );
+
+        let discord_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/oauth2/token")
+                .header("Content-Type", "application/x-www-form-urlencoded")
+                .x_www_form_urlencoded_tuple("client_id", "foo-discord-id")
+                .x_www_form_urlencoded_tuple("client_secret", "foo-discord-secret")
+                .x_www_form_urlencoded_tuple("code", "foo-code")
+                .x_www_form_urlencoded_tuple("grant_type", "authorization_code")
+                .x_www_form_urlencoded_tuple("redirect_uri", "https://foo.test/callback");
+            then.status(200).json_body(json!({
+                "access_token": "6qrZcUqja7812RVdnEKjpzOL4CvHBFG",
+                "token_type": "Bearer",
+                "expires_in": 604800,
+            }));
+        });
+
+        let result = discord_client.new_access_token("foo-code", None).await;
+
+        discord_mock.assert();
+        assert_ok_eq!(
+            result,
+            AccessToken::from("6qrZcUqja7812RVdnEKjpzOL4CvHBFG".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn get_user() {
+        let server = MockServer::start();
+
+        let discord_client = DiscordClient::new(
+            "foo-discord-id".into(),
+            "foo-discord-secret".into(),
+            "https://foo.test/callback".into(),
+            "".into(),
+            server.url("/api/users/@me"),
+        );
+
+        let discord_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/api/users/@me")
+                .header("Authorization", "Bearer foo-access-token");
+            then.status(200).json_body(json!({
+                "id": "80351110224678912",
+                "username": "nelly",
+                "global_name": "Nelly",
+                "avatar": "8342729096ea3675442027381ff50dfe",
+            }));
+        });
+
+        let access_token = AccessToken::from("foo-access-token".to_string());
+        let result = discord_client.get_user(&access_token).await;
+
+        discord_mock.assert();
+        assert_ok_eq!(
+            result,
+            (
+                Identity::DiscordId(80351110224678912.into()),
+                IdentityProfile {
+                    login: "nelly".to_string(),
+                    name: Some("Nelly".to_string()),
+                    avatar_url: Some(
+                        "https://cdn.discordapp.com/avatars/80351110224678912/8342729096ea3675442027381ff50dfe.png"
+                            .to_string()
+                    ),
+                    created_at: None,
+                    public_repos: None,
+                    followers: None,
+                    suspended: false,
+                }
+            )
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use crate::domain::errors::AuthenticationError;
+use crate::domain::errors::{AuthenticationError, RepositoryError};
 
 impl From<reqwest::Error> for AuthenticationError {
     fn from(e: reqwest::Error) -> Self {
@@ -8,3 +8,9 @@ impl From<reqwest::Error> for AuthenticationError {
         AuthenticationError::Http(Box::new(e))
     }
 }
+
+impl From<rusqlite::Error> for RepositoryError {
+    fn from(e: rusqlite::Error) -> Self {
+        RepositoryError::Storage(Box::new(e))
+    }
+}
@@ -0,0 +1,241 @@
+use rocket::serde::{Deserialize, Serialize};
+
+use crate::domain::{
+    errors::AuthenticationError,
+    errors::IdentificationError,
+    services::identity_provider::IdentityProvider,
+    value_objects::{AccessToken, Identity, IdentityProfile},
+};
+
+#[derive(Clone)]
+pub struct GitLabClient {
+    http_client: reqwest::Client,
+
+    access_token_url: String,
+    user_api_url: String,
+
+    gitlab_id: String,
+    gitlab_secret: String,
+    redirect_uri: String,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct AccessTokenRequestBody<'r> {
+    client_id: &'r str,
+    client_secret: &'r str,
+    code: &'r str,
+    grant_type: &'r str,
+    redirect_uri: &'r str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    code_verifier: Option<&'r str>,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct AccessTokenResponseBody {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct UserResponseBody {
+    id: u64,
+    username: String,
+    name: Option<String>,
+    avatar_url: Option<String>,
+    created_at: Option<String>,
+}
+
+impl GitLabClient {
+    pub fn new(
+        gitlab_id: String,
+        gitlab_secret: String,
+        redirect_uri: String,
+        access_token_url: String,
+        user_api_url: String,
+    ) -> Self {
+        GitLabClient {
+            http_client: reqwest::Client::new(),
+            access_token_url,
+            user_api_url,
+            gitlab_id,
+            gitlab_secret,
+            redirect_uri,
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl IdentityProvider for GitLabClient {
+    async fn new_access_token(
+        &self,
+        authorization_code: &str,
+        code_verifier: Option<String>,
+    ) -> Result<AccessToken, AuthenticationError> {
+        let request_body = AccessTokenRequestBody {
+            client_id: &self.gitlab_id,
+            client_secret: &self.gitlab_secret,
+            code: authorization_code,
+            grant_type: "authorization_code",
+            redirect_uri: &self.redirect_uri,
+            code_verifier: code_verifier.as_deref(),
+        };
+
+        let response = self
+            .http_client
+            .post(&self.access_token_url)
+            .json(&request_body)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .send()
+            .await
+            .map_err(|e| AuthenticationError::Http(Box::new(e)))?
+            .error_for_status()
+            .map_err(|e| AuthenticationError::Http(Box::new(e)))?;
+
+        let response = response
+            .json::<AccessTokenResponseBody>()
+            .await
+            .map_err(|e| AuthenticationError::Serde(Box::new(e)))?;
+
+        Ok(AccessToken::from(response.access_token))
+    }
+
+    async fn get_user(
+        &self,
+        access_token: &AccessToken,
+    ) -> Result<(Identity, IdentityProfile), IdentificationError> {
+        let response = self
+            .http_client
+            .get(&self.user_api_url)
+            .header(reqwest::header::ACCEPT, "application/json")
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| IdentificationError::Http(Box::new(e)))?
+            .error_for_status()
+            .map_err(|e| IdentificationError::Http(Box::new(e)))?;
+
+        let response = response
+            .json::<UserResponseBody>()
+            .await
+            .map_err(|e| IdentificationError::Serde(Box::new(e)))?;
+
+        Ok((
+            Identity::GitLabId(response.id.into()),
+            IdentityProfile {
+                login: response.username,
+                name: response.name,
+                avatar_url: response.avatar_url,
+                created_at: response.created_at,
+                public_repos: None,
+                followers: None,
+                suspended: false,
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::domain::{
+        services::identity_provider::IdentityProvider,
+        value_objects::{AccessToken, Identity, IdentityProfile},
+    };
+
+    use super::GitLabClient;
+    use claim::*;
+    use httpmock::prelude::*;
+    use rocket::{serde::json::serde_json, tokio};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn new_access_token() {
+        let server = MockServer::start();
+
+        let gitlab_client = GitLabClient::new(
+            "foo-gitlab-id".into(),
+            "foo-gitlab-secret".into(),
+            "https://foo.test/callback".into(),
+            server.url("/oauth/token"),
+            "".into(),
+        );
+
+        let gitlab_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/oauth/token")
+                .header("Accept", "application/json")
+                .json_body(json!({
+                    "client_id": "foo-gitlab-id",
+                    "client_secret": "foo-gitlab-secret",
+                    "code": "foo-code",
+                    "grant_type": "authorization_code",
+                    "redirect_uri": "https://foo.test/callback",
+                }));
+            then.status(200).json_body(json!({
+                "access_token": "de6780bc506a0446309bd9362820ba8aed28aa506c71eedbe1c5c4f9dd350e54",
+                "token_type": "bearer",
+                "expires_in": 7200,
+            }));
+        });
+
+        let result = gitlab_client.new_access_token("foo-code", None).await;
+
+        gitlab_mock.assert();
+        assert_ok_eq!(
+            result,
+            AccessToken::from(
+                "de6780bc506a0446309bd9362820ba8aed28aa506c71eedbe1c5c4f9dd350e54".to_string()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn get_user() {
+        let server = MockServer::start();
+
+        let gitlab_client = GitLabClient::new(
+            "foo-gitlab-id".into(),
+            "foo-gitlab-secret".into(),
+            "https://foo.test/callback".into(),
+            "".into(),
+            server.url("/api/v4/user"),
+        );
+
+        let gitlab_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/api/v4/user")
+                .header("Authorization", "Bearer foo-access-token");
+            then.status(200).json_body(json!({
+                "id": 42,
+                "username": "octocat",
+                "name": "Octo Cat",
+                "avatar_url": "https://gitlab.com/uploads/user/avatar/42/avatar.png",
+                "created_at": "2012-05-23T08:00:58Z",
+            }));
+        });
+
+        let access_token = AccessToken::from("foo-access-token".to_string());
+        let result = gitlab_client.get_user(&access_token).await;
+
+        gitlab_mock.assert();
+        assert_ok_eq!(
+            result,
+            (
+                Identity::GitLabId(42.into()),
+                IdentityProfile {
+                    login: "octocat".to_string(),
+                    name: Some("Octo Cat".to_string()),
+                    avatar_url: Some(
+                        "https://gitlab.com/uploads/user/avatar/42/avatar.png".to_string()
+                    ),
+                    created_at: Some("2012-05-23T08:00:58Z".to_string()),
+                    public_repos: None,
+                    followers: None,
+                    suspended: false,
+                }
+            )
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+use crate::domain::{
+    errors::AuthorizationStateError, services::authorization_state_store::AuthorizationStateStore,
+    value_objects::AuthorizationState,
+};
+
+struct IssuedState {
+    authorization_state: AuthorizationState,
+    /// Order of issuance, telling the oldest state apart
+    issuance: u64,
+}
+
+#[derive(Default)]
+struct IssuedStates {
+    states: HashMap<String, IssuedState>,
+    issuances: u64,
+}
+
+/// OAuth states of the issued authorization requests, kept until `ttl`. Anybody can request
+/// states, so at most `capacity` of them are kept: the oldest one makes room for a new one.
+pub struct InMemoryAuthorizationStateStore {
+    capacity: usize,
+    ttl: Duration,
+    states: Mutex<IssuedStates>,
+}
+
+impl InMemoryAuthorizationStateStore {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        InMemoryAuthorizationStateStore {
+            capacity,
+            ttl,
+            states: Mutex::new(IssuedStates::default()),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl AuthorizationStateStore for InMemoryAuthorizationStateStore {
+    async fn issue(&self) -> AuthorizationState {
+        let now = SystemTime::now();
+        let authorization_state = AuthorizationState {
+            state: format!("{:032x}", rand::random::<u128>()),
+            // 32 random bytes give the 43 characters long verifier recommended by RFC 7636
+            code_verifier: base64::encode_config(
+                rand::random::<[u8; 32]>(),
+                base64::URL_SAFE_NO_PAD,
+            ),
+            expires_at: now + self.ttl,
+        };
+
+        let mut issued_states = self.states.lock().unwrap();
+        // forget about expired states, nobody can use them anymore
+        issued_states
+            .states
+            .retain(|_, issued| issued.authorization_state.expires_at > now);
+        // then about the oldest one
+        if issued_states.states.len() >= self.capacity {
+            let oldest = issued_states
+                .states
+                .iter()
+                .min_by_key(|(_, issued)| issued.issuance)
+                .map(|(state, _)| state.clone());
+            if let Some(oldest) = oldest {
+                issued_states.states.remove(&oldest);
+            }
+        }
+
+        issued_states.issuances += 1;
+        let issuance = issued_states.issuances;
+        issued_states.states.insert(
+            authorization_state.state.clone(),
+            IssuedState {
+                authorization_state: authorization_state.clone(),
+                issuance,
+            },
+        );
+
+        authorization_state
+    }
+
+    async fn consume(&self, state: &str) -> Result<AuthorizationState, AuthorizationStateError> {
+        let authorization_state = self
+            .states
+            .lock()
+            .unwrap()
+            .states
+            .remove(state)
+            .ok_or(AuthorizationStateError::Unknown)?
+            .authorization_state;
+
+        if authorization_state.expires_at <= SystemTime::now() {
+            return Err(AuthorizationStateError::Expired);
+        }
+
+        Ok(authorization_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use claim::{assert_err, assert_ok_eq};
+    use rocket::tokio;
+
+    use super::InMemoryAuthorizationStateStore;
+    use crate::domain::{
+        errors::AuthorizationStateError,
+        services::authorization_state_store::AuthorizationStateStore,
+        value_objects::AuthorizationState,
+    };
+
+    #[tokio::test]
+    async fn state_can_be_consumed_once() {
+        let store = InMemoryAuthorizationStateStore::new(2, Duration::from_secs(60));
+
+        let authorization_state = store.issue().await;
+
+        assert_ok_eq!(
+            store.consume(&authorization_state.state).await,
+            authorization_state.clone()
+        );
+        assert!(matches!(
+            store.consume(&authorization_state.state).await,
+            Err(AuthorizationStateError::Unknown)
+        ));
+    }
+
+    #[tokio::test]
+    async fn unknown_state_is_rejected() {
+        let store = InMemoryAuthorizationStateStore::new(2, Duration::from_secs(60));
+
+        assert_err!(store.consume("foo-state").await);
+    }
+
+    #[tokio::test]
+    async fn expired_state_is_rejected() {
+        let store = InMemoryAuthorizationStateStore::new(2, Duration::ZERO);
+
+        let authorization_state = store.issue().await;
+
+        assert!(matches!(
+            store.consume(&authorization_state.state).await,
+            Err(AuthorizationStateError::Expired)
+        ));
+    }
+
+    #[tokio::test]
+    async fn code_verifier_follows_rfc_7636() {
+        let store = InMemoryAuthorizationStateStore::new(2, Duration::from_secs(60));
+
+        let authorization_state = store.issue().await;
+
+        assert_eq!(authorization_state.code_verifier.len(), 43);
+        assert!(authorization_state
+            .code_verifier
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+
+    #[tokio::test]
+    async fn oldest_state_is_forgotten_when_full() {
+        let store = InMemoryAuthorizationStateStore::new(2, Duration::from_secs(60));
+
+        let oldest = store.issue().await;
+        let other = store.issue().await;
+        let newest = store.issue().await;
+
+        assert!(matches!(
+            store.consume(&oldest.state).await,
+            Err(AuthorizationStateError::Unknown)
+        ));
+        assert_ok_eq!(store.consume(&other.state).await, other);
+        assert_ok_eq!(store.consume(&newest.state).await, newest);
+    }
+
+    #[test]
+    fn code_challenge_follows_rfc_7636() {
+        // example of RFC 7636 appendix B
+        let authorization_state = AuthorizationState {
+            state: "foo-state".to_string(),
+            code_verifier: "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".to_string(),
+            expires_at: std::time::SystemTime::now(),
+        };
+
+        assert_eq!(
+            authorization_state.code_challenge(),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+use crate::domain::{
+    services::idempotency_store::IdempotencyStore, value_objects::IdempotentRequest,
+};
+
+/// Default time after which a request that has not completed can be processed again
+const DEFAULT_LEASE: Duration = Duration::from_secs(60);
+
+struct Entry<T> {
+    fingerprint: String,
+    response: Option<T>,
+    expires_at: SystemTime,
+}
+
+pub struct InMemoryIdempotencyStore<T> {
+    ttl: Duration,
+    lease: Duration,
+    entries: Mutex<HashMap<String, Entry<T>>>,
+}
+
+impl<T> InMemoryIdempotencyStore<T> {
+    pub fn new(ttl: Duration) -> Self {
+        InMemoryIdempotencyStore {
+            ttl,
+            lease: DEFAULT_LEASE,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the time after which a request that has not completed, e.g. because it has been cancelled,
+    /// can be processed again.
+    pub fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+}
+
+#[rocket::async_trait]
+impl<T> IdempotencyStore<T> for InMemoryIdempotencyStore<T>
+where
+    T: Clone + Send + Sync,
+{
+    async fn begin(&self, key: &str, fingerprint: &str) -> IdempotentRequest<T> {
+        let now = SystemTime::now();
+
+        let mut entries = self.entries.lock().unwrap();
+        // forget about expired keys and leases, their requests can be processed again
+        entries.retain(|_, entry| entry.expires_at > now);
+
+        match entries.get(key) {
+            Some(entry) if entry.fingerprint != fingerprint => IdempotentRequest::Mismatch,
+            Some(Entry {
+                response: Some(response),
+                ..
+            }) => IdempotentRequest::Completed(response.clone()),
+            Some(Entry { response: None, .. }) => IdempotentRequest::InProgress,
+            None => {
+                entries.insert(
+                    key.to_string(),
+                    Entry {
+                        fingerprint: fingerprint.to_string(),
+                        response: None,
+                        expires_at: now + self.lease,
+                    },
+                );
+                IdempotentRequest::New
+            }
+        }
+    }
+
+    async fn complete(&self, key: &str, fingerprint: &str, response: T) {
+        self.entries.lock().unwrap().insert(
+            key.to_string(),
+            Entry {
+                fingerprint: fingerprint.to_string(),
+                response: Some(response),
+                expires_at: SystemTime::now() + self.ttl,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rocket::tokio;
+
+    use super::InMemoryIdempotencyStore;
+    use crate::domain::{
+        services::idempotency_store::IdempotencyStore, value_objects::IdempotentRequest,
+    };
+
+    #[tokio::test]
+    async fn completed_response_is_replayed() {
+        let store = InMemoryIdempotencyStore::new(Duration::from_secs(60));
+
+        assert_eq!(store.begin("foo-key", "foo").await, IdempotentRequest::New);
+        store.complete("foo-key", "foo", 42).await;
+
+        assert_eq!(
+            store.begin("foo-key", "foo").await,
+            IdempotentRequest::Completed(42)
+        );
+        assert_eq!(store.begin("bar-key", "foo").await, IdempotentRequest::New);
+    }
+
+    #[tokio::test]
+    async fn concurrent_request_is_in_progress() {
+        let store = InMemoryIdempotencyStore::<u32>::new(Duration::from_secs(60));
+
+        assert_eq!(store.begin("foo-key", "foo").await, IdempotentRequest::New);
+        assert_eq!(
+            store.begin("foo-key", "foo").await,
+            IdempotentRequest::InProgress
+        );
+    }
+
+    #[tokio::test]
+    async fn uncompleted_request_is_processed_again_after_its_lease() {
+        let store = InMemoryIdempotencyStore::<u32>::new(Duration::from_secs(60))
+            .with_lease(Duration::ZERO);
+
+        assert_eq!(store.begin("foo-key", "foo").await, IdempotentRequest::New);
+        assert_eq!(store.begin("foo-key", "foo").await, IdempotentRequest::New);
+    }
+
+    #[tokio::test]
+    async fn key_cannot_be_reused_for_another_request() {
+        let store = InMemoryIdempotencyStore::new(Duration::from_secs(60));
+
+        assert_eq!(store.begin("foo-key", "foo").await, IdempotentRequest::New);
+        assert_eq!(
+            store.begin("foo-key", "bar").await,
+            IdempotentRequest::Mismatch
+        );
+
+        store.complete("foo-key", "foo", 42).await;
+        assert_eq!(
+            store.begin("foo-key", "bar").await,
+            IdempotentRequest::Mismatch
+        );
+    }
+
+    #[tokio::test]
+    async fn expired_key_is_forgotten() {
+        let store = InMemoryIdempotencyStore::new(Duration::ZERO);
+
+        assert_eq!(store.begin("foo-key", "foo").await, IdempotentRequest::New);
+        store.complete("foo-key", "foo", 42).await;
+
+        assert_eq!(store.begin("foo-key", "foo").await, IdempotentRequest::New);
+    }
+}
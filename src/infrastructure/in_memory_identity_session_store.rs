@@ -0,0 +1,151 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+use crate::domain::{
+    errors::IdentitySessionError,
+    services::identity_session_store::IdentitySessionStore,
+    value_objects::{Identity, IdentityProfile, IdentitySession},
+};
+
+pub struct InMemoryIdentitySessionStore {
+    ttl: Duration,
+    sessions: Mutex<HashMap<String, IdentitySession>>,
+}
+
+impl InMemoryIdentitySessionStore {
+    pub fn new(ttl: Duration) -> Self {
+        InMemoryIdentitySessionStore {
+            ttl,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl IdentitySessionStore for InMemoryIdentitySessionStore {
+    async fn open(&self, identity: Identity, profile: IdentityProfile) -> IdentitySession {
+        let now = SystemTime::now();
+        let session = IdentitySession {
+            id: format!("{:032x}", rand::random::<u128>()),
+            identity,
+            profile,
+            expires_at: now + self.ttl,
+        };
+
+        let mut sessions = self.sessions.lock().unwrap();
+        // forget about expired sessions, nobody can use them anymore
+        sessions.retain(|_, session| session.expires_at > now);
+        sessions.insert(session.id.clone(), session.clone());
+
+        session
+    }
+
+    async fn get(&self, id: &str) -> Result<IdentitySession, IdentitySessionError> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions.get(id).ok_or(IdentitySessionError::Unknown)?;
+        if session.expires_at > SystemTime::now() {
+            return Ok(session.clone());
+        }
+
+        sessions.remove(id);
+        Err(IdentitySessionError::Expired)
+    }
+
+    async fn close(&self, id: &str) -> Result<IdentitySession, IdentitySessionError> {
+        let session = self
+            .sessions
+            .lock()
+            .unwrap()
+            .remove(id)
+            .ok_or(IdentitySessionError::Unknown)?;
+
+        if session.expires_at <= SystemTime::now() {
+            return Err(IdentitySessionError::Expired);
+        }
+
+        Ok(session)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use claim::{assert_err, assert_ok_eq};
+    use rocket::tokio;
+
+    use super::InMemoryIdentitySessionStore;
+    use crate::domain::{
+        errors::IdentitySessionError,
+        services::identity_session_store::IdentitySessionStore,
+        value_objects::{Identity, IdentityProfile},
+    };
+
+    fn octocat_profile() -> IdentityProfile {
+        IdentityProfile {
+            login: "octocat".to_string(),
+            name: None,
+            avatar_url: None,
+            created_at: None,
+            public_repos: None,
+            followers: None,
+            suspended: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn session_can_be_closed_once() {
+        let store = InMemoryIdentitySessionStore::new(Duration::from_secs(60));
+
+        let session = store
+            .open(Identity::GitHubId(42.into()), octocat_profile())
+            .await;
+
+        assert_ok_eq!(store.close(&session.id).await, session.clone());
+        assert!(matches!(
+            store.close(&session.id).await,
+            Err(IdentitySessionError::Unknown)
+        ));
+    }
+
+    #[tokio::test]
+    async fn session_is_left_open_when_read() {
+        let store = InMemoryIdentitySessionStore::new(Duration::from_secs(60));
+
+        let session = store
+            .open(Identity::GitHubId(42.into()), octocat_profile())
+            .await;
+
+        assert_ok_eq!(store.get(&session.id).await, session.clone());
+        assert_ok_eq!(store.get(&session.id).await, session.clone());
+        assert_ok_eq!(store.close(&session.id).await, session.clone());
+        assert!(matches!(
+            store.get(&session.id).await,
+            Err(IdentitySessionError::Unknown)
+        ));
+    }
+
+    #[tokio::test]
+    async fn unknown_session_is_rejected() {
+        let store = InMemoryIdentitySessionStore::new(Duration::from_secs(60));
+
+        assert_err!(store.close("foo-session").await);
+    }
+
+    #[tokio::test]
+    async fn expired_session_is_rejected() {
+        let store = InMemoryIdentitySessionStore::new(Duration::ZERO);
+
+        let session = store
+            .open(Identity::GitHubId(42.into()), octocat_profile())
+            .await;
+
+        assert!(matches!(
+            store.close(&session.id).await,
+            Err(IdentitySessionError::Expired)
+        ));
+    }
+}
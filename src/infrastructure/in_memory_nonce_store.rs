@@ -0,0 +1,176 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+use crate::domain::{
+    errors::ChallengeError,
+    services::nonce_store::NonceStore,
+    value_objects::{Challenge, Nonce},
+};
+
+struct IssuedNonce {
+    account_address: String,
+    expires_at: SystemTime,
+    /// Order of issuance, telling the oldest nonce apart
+    issuance: u64,
+}
+
+#[derive(Default)]
+struct IssuedNonces {
+    nonces: HashMap<Nonce, IssuedNonce>,
+    issuances: u64,
+}
+
+/// Nonces of the issued challenges. They are kept until `ttl`, and the oldest nonce is forgotten
+/// once `capacity` nonces are issued, so that the store does not grow without bounds.
+pub struct InMemoryNonceStore {
+    capacity: usize,
+    ttl: Duration,
+    nonces: Mutex<IssuedNonces>,
+}
+
+impl InMemoryNonceStore {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        InMemoryNonceStore {
+            capacity,
+            ttl,
+            nonces: Mutex::new(IssuedNonces::default()),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl NonceStore for InMemoryNonceStore {
+    async fn issue(&self, account_address: &str) -> Challenge {
+        let now = SystemTime::now();
+        let challenge = Challenge {
+            nonce: Nonce::from(rand::random::<u128>()),
+            expires_at: now + self.ttl,
+        };
+
+        let mut issued_nonces = self.nonces.lock().unwrap();
+        // forget about expired nonces, nobody can use them anymore
+        issued_nonces
+            .nonces
+            .retain(|_, issued| issued.expires_at > now);
+        // then about the oldest one
+        if issued_nonces.nonces.len() >= self.capacity {
+            let oldest = issued_nonces
+                .nonces
+                .iter()
+                .min_by_key(|(_, issued)| issued.issuance)
+                .map(|(nonce, _)| *nonce);
+            if let Some(oldest) = oldest {
+                issued_nonces.nonces.remove(&oldest);
+            }
+        }
+
+        issued_nonces.issuances += 1;
+        let issuance = issued_nonces.issuances;
+        issued_nonces.nonces.insert(
+            challenge.nonce,
+            IssuedNonce {
+                account_address: account_address.to_string(),
+                expires_at: challenge.expires_at,
+                issuance,
+            },
+        );
+
+        challenge
+    }
+
+    async fn consume(&self, nonce: &Nonce, account_address: &str) -> Result<(), ChallengeError> {
+        let mut issued_nonces = self.nonces.lock().unwrap();
+        let nonces = &mut issued_nonces.nonces;
+
+        let issued = nonces.get(nonce).ok_or(ChallengeError::Unknown)?;
+        // nonces sent for another account are left to the account they were issued for
+        if issued.account_address != account_address {
+            return Err(ChallengeError::OtherAccount);
+        }
+        let expired = issued.expires_at <= SystemTime::now();
+
+        nonces.remove(nonce);
+        if expired {
+            return Err(ChallengeError::Expired);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use claim::{assert_err, assert_ok};
+    use rocket::tokio;
+
+    use super::InMemoryNonceStore;
+    use crate::domain::{
+        errors::ChallengeError, services::nonce_store::NonceStore, value_objects::Nonce,
+    };
+
+    #[tokio::test]
+    async fn nonce_can_be_consumed_once() {
+        let store = InMemoryNonceStore::new(2, Duration::from_secs(60));
+
+        let challenge = store.issue("0x65f1").await;
+
+        assert_ok!(store.consume(&challenge.nonce, "0x65f1").await);
+        assert!(matches!(
+            store.consume(&challenge.nonce, "0x65f1").await,
+            Err(ChallengeError::Unknown)
+        ));
+    }
+
+    #[tokio::test]
+    async fn unknown_nonce_is_rejected() {
+        let store = InMemoryNonceStore::new(2, Duration::from_secs(60));
+
+        assert_err!(store.consume(&Nonce::from(42), "0x65f1").await);
+    }
+
+    #[tokio::test]
+    async fn expired_nonce_is_rejected() {
+        let store = InMemoryNonceStore::new(2, Duration::ZERO);
+
+        let challenge = store.issue("0x65f1").await;
+
+        assert!(matches!(
+            store.consume(&challenge.nonce, "0x65f1").await,
+            Err(ChallengeError::Expired)
+        ));
+    }
+
+    #[tokio::test]
+    async fn nonce_is_bound_to_account() {
+        let store = InMemoryNonceStore::new(2, Duration::from_secs(60));
+
+        let challenge = store.issue("0x65f1").await;
+
+        assert!(matches!(
+            store.consume(&challenge.nonce, "0x65f2").await,
+            Err(ChallengeError::OtherAccount)
+        ));
+        assert_ok!(store.consume(&challenge.nonce, "0x65f1").await);
+    }
+
+    #[tokio::test]
+    async fn oldest_nonce_is_forgotten_when_full() {
+        let store = InMemoryNonceStore::new(2, Duration::from_secs(60));
+
+        let oldest = store.issue("0x65f1").await;
+        let other = store.issue("0x65f1").await;
+        let newest = store.issue("0x65f1").await;
+
+        assert!(matches!(
+            store.consume(&oldest.nonce, "0x65f1").await,
+            Err(ChallengeError::Unknown)
+        ));
+        assert_ok!(store.consume(&other.nonce, "0x65f1").await);
+        assert_ok!(store.consume(&newest.nonce, "0x65f1").await);
+    }
+}
@@ -0,0 +1,536 @@
+use rocket::serde::{
+    json::{serde_json, Value},
+    Deserialize, Serialize,
+};
+use starknet::core::types::FieldElement;
+use thiserror::Error;
+
+use crate::domain::value_objects::TransactionStatus;
+
+use super::starknet_provider::StarkNetProviderError;
+
+/// Error codes of the StarkNet JSON-RPC specification
+const CONTRACT_NOT_FOUND: i64 = 20;
+const INVALID_MESSAGE_SELECTOR: i64 = 21;
+const INVALID_CALL_DATA: i64 = 22;
+const TRANSACTION_HASH_NOT_FOUND: i64 = 25;
+const CONTRACT_ERROR: i64 = 40;
+
+/// Reads the StarkNet state from a JSON-RPC node, such as Pathfinder, Juno or a devnet,
+/// and sends the transactions of the service to it.
+pub struct JsonRpcProvider {
+    http_client: reqwest::Client,
+    url: String,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "rocket::serde")]
+struct JsonRpcRequest<'r> {
+    jsonrpc: &'r str,
+    id: u64,
+    method: &'r str,
+    params: Value,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct JsonRpcResponse<R> {
+    result: Option<R>,
+    error: Option<JsonRpcError>,
+}
+
+#[derive(Debug, Error, Deserialize)]
+#[serde(crate = "rocket::serde")]
+#[error("JSON-RPC error {code}: {message}")]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct TransactionReceipt {
+    status: String,
+    status_data: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct FeeEstimate {
+    overall_fee: String,
+}
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+struct InvokeTransactionResult {
+    transaction_hash: String,
+}
+
+/// Invoke transaction of an account, in its version 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvokeTransaction {
+    pub sender_address: FieldElement,
+    pub calldata: Vec<FieldElement>,
+    pub max_fee: FieldElement,
+    pub signature: Vec<FieldElement>,
+    pub nonce: FieldElement,
+}
+
+impl InvokeTransaction {
+    fn to_json(&self) -> Value {
+        let felts = |felts: &[FieldElement]| {
+            felts
+                .iter()
+                .map(|felt| format!("{:#x}", felt))
+                .collect::<Vec<_>>()
+        };
+
+        serde_json::json!({
+            "type": "INVOKE",
+            "version": "0x1",
+            "sender_address": format!("{:#x}", self.sender_address),
+            "calldata": felts(&self.calldata),
+            "max_fee": format!("{:#x}", self.max_fee),
+            "signature": felts(&self.signature),
+            "nonce": format!("{:#x}", self.nonce),
+        })
+    }
+}
+
+fn parse_felt(value: &str) -> Result<FieldElement, StarkNetProviderError> {
+    FieldElement::from_hex_be(value).map_err(|_| {
+        StarkNetProviderError::Provider(format!("Invalid field element {}", value).into())
+    })
+}
+
+impl JsonRpcProvider {
+    pub fn new(url: String) -> Self {
+        JsonRpcProvider {
+            http_client: reqwest::Client::new(),
+            url,
+        }
+    }
+
+    async fn request<R: for<'de> Deserialize<'de>>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<Result<R, JsonRpcError>, StarkNetProviderError> {
+        let response = self
+            .http_client
+            .post(&self.url)
+            .json(&JsonRpcRequest {
+                jsonrpc: "2.0",
+                id: 1,
+                method,
+                params,
+            })
+            .send()
+            .await
+            .map_err(|e| StarkNetProviderError::Provider(Box::new(e)))?
+            .error_for_status()
+            .map_err(|e| StarkNetProviderError::Provider(Box::new(e)))?
+            .json::<JsonRpcResponse<R>>()
+            .await
+            .map_err(|e| StarkNetProviderError::Provider(Box::new(e)))?;
+
+        match (response.result, response.error) {
+            (_, Some(error)) => Ok(Err(error)),
+            (Some(result), None) => Ok(Ok(result)),
+            (None, None) => Err(StarkNetProviderError::Provider(
+                format!("Empty response to {}", method).into(),
+            )),
+        }
+    }
+
+    pub async fn call(
+        &self,
+        contract_address: FieldElement,
+        entry_point_selector: FieldElement,
+        calldata: Vec<FieldElement>,
+    ) -> Result<Vec<FieldElement>, StarkNetProviderError> {
+        let params = serde_json::json!({
+            "request": {
+                "contract_address": format!("{:#x}", contract_address),
+                "entry_point_selector": format!("{:#x}", entry_point_selector),
+                "calldata": calldata
+                    .iter()
+                    .map(|felt| format!("{:#x}", felt))
+                    .collect::<Vec<_>>(),
+            },
+            "block_id": "latest",
+        });
+
+        match self.request::<Vec<String>>("starknet_call", params).await? {
+            Ok(result) => result.iter().map(|felt| parse_felt(felt)).collect(),
+            Err(error) => Err(match error.code {
+                CONTRACT_NOT_FOUND => StarkNetProviderError::ContractNotFound,
+                INVALID_MESSAGE_SELECTOR | INVALID_CALL_DATA | CONTRACT_ERROR => {
+                    StarkNetProviderError::Contract(Box::new(error))
+                }
+                _ => StarkNetProviderError::Provider(Box::new(error)),
+            }),
+        }
+    }
+
+    pub async fn get_class_hash_at(
+        &self,
+        contract_address: FieldElement,
+    ) -> Result<FieldElement, StarkNetProviderError> {
+        let params = serde_json::json!({
+            "block_id": "latest",
+            "contract_address": format!("{:#x}", contract_address),
+        });
+
+        match self
+            .request::<String>("starknet_getClassHashAt", params)
+            .await?
+        {
+            Ok(class_hash) => parse_felt(&class_hash),
+            Err(error) if error.code == CONTRACT_NOT_FOUND => {
+                Err(StarkNetProviderError::ContractNotFound)
+            }
+            Err(error) => Err(StarkNetProviderError::Provider(Box::new(error))),
+        }
+    }
+
+    /// Overall fee of `transaction`, in wei.
+    pub async fn estimate_fee(
+        &self,
+        transaction: &InvokeTransaction,
+    ) -> Result<u64, StarkNetProviderError> {
+        let params = serde_json::json!({
+            "request": transaction.to_json(),
+            "block_id": "latest",
+        });
+
+        match self
+            .request::<FeeEstimate>("starknet_estimateFee", params)
+            .await?
+        {
+            Ok(estimate) => u64::from_str_radix(estimate.overall_fee.trim_start_matches("0x"), 16)
+                .map_err(|_| {
+                    StarkNetProviderError::Provider(
+                        format!("Invalid fee estimate {}", estimate.overall_fee).into(),
+                    )
+                }),
+            Err(error) if error.code == CONTRACT_ERROR => {
+                Err(StarkNetProviderError::Contract(Box::new(error)))
+            }
+            Err(error) => Err(StarkNetProviderError::Provider(Box::new(error))),
+        }
+    }
+
+    /// Sends `transaction`, and returns its hash.
+    pub async fn add_invoke_transaction(
+        &self,
+        transaction: &InvokeTransaction,
+    ) -> Result<FieldElement, StarkNetProviderError> {
+        let params = serde_json::json!({
+            "invoke_transaction": transaction.to_json(),
+        });
+
+        match self
+            .request::<InvokeTransactionResult>("starknet_addInvokeTransaction", params)
+            .await?
+        {
+            Ok(result) => parse_felt(&result.transaction_hash),
+            Err(error) => Err(StarkNetProviderError::Provider(Box::new(error))),
+        }
+    }
+
+    pub async fn get_transaction_status(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> Result<TransactionStatus, StarkNetProviderError> {
+        let params = serde_json::json!({
+            "transaction_hash": format!("{:#x}", transaction_hash),
+        });
+
+        let receipt = match self
+            .request::<TransactionReceipt>("starknet_getTransactionReceipt", params)
+            .await?
+        {
+            Ok(receipt) => receipt,
+            Err(error) if error.code == TRANSACTION_HASH_NOT_FOUND => {
+                return Ok(TransactionStatus::NotReceived)
+            }
+            Err(error) => return Err(StarkNetProviderError::Provider(Box::new(error))),
+        };
+
+        match receipt.status.as_str() {
+            "RECEIVED" | "PENDING" => Ok(TransactionStatus::Pending),
+            "ACCEPTED_ON_L2" => Ok(TransactionStatus::AcceptedOnL2),
+            "ACCEPTED_ON_L1" => Ok(TransactionStatus::AcceptedOnL1),
+            "REJECTED" => Ok(TransactionStatus::Rejected {
+                reason: receipt.status_data,
+            }),
+            status => Err(StarkNetProviderError::Provider(
+                format!("Unknown transaction status {}", status).into(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use claim::{assert_matches, assert_ok_eq};
+    use httpmock::prelude::*;
+    use rocket::{serde::json::serde_json::json, tokio};
+    use starknet::core::types::FieldElement;
+
+    use super::{InvokeTransaction, JsonRpcProvider};
+    use crate::{
+        domain::value_objects::TransactionStatus,
+        infrastructure::starknet_provider::StarkNetProviderError,
+    };
+
+    #[tokio::test]
+    async fn call() {
+        let server = MockServer::start();
+        let provider = JsonRpcProvider::new(server.url("/rpc"));
+
+        let rpc_mock = server.mock(|when, then| {
+            when.method(POST).path("/rpc").json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "starknet_call",
+                "params": {
+                    "request": {
+                        "contract_address": "0x42",
+                        "entry_point_selector": "0x1",
+                        "calldata": ["0x2", "0x3"],
+                    },
+                    "block_id": "latest",
+                },
+            }));
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": ["0x56414c4944"],
+            }));
+        });
+
+        let result = provider
+            .call(
+                FieldElement::from(0x42u64),
+                FieldElement::ONE,
+                vec![FieldElement::from(2u64), FieldElement::from(3u64)],
+            )
+            .await;
+
+        rpc_mock.assert();
+        assert_ok_eq!(result, vec![FieldElement::from(0x56414c4944u64)]);
+    }
+
+    #[tokio::test]
+    async fn call_on_undeployed_contract() {
+        let server = MockServer::start();
+        let provider = JsonRpcProvider::new(server.url("/rpc"));
+
+        server.mock(|when, then| {
+            when.method(POST).path("/rpc");
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "error": { "code": 20, "message": "Contract not found" },
+            }));
+        });
+
+        let result = provider
+            .call(FieldElement::from(0x42u64), FieldElement::ONE, vec![])
+            .await;
+
+        assert_matches!(result, Err(StarkNetProviderError::ContractNotFound));
+    }
+
+    #[tokio::test]
+    async fn call_reverted_by_contract() {
+        let server = MockServer::start();
+        let provider = JsonRpcProvider::new(server.url("/rpc"));
+
+        server.mock(|when, then| {
+            when.method(POST).path("/rpc");
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "error": { "code": 40, "message": "Contract error" },
+            }));
+        });
+
+        let result = provider
+            .call(FieldElement::from(0x42u64), FieldElement::ONE, vec![])
+            .await;
+
+        assert_matches!(result, Err(StarkNetProviderError::Contract(_)));
+    }
+
+    #[tokio::test]
+    async fn get_class_hash_at() {
+        let server = MockServer::start();
+        let provider = JsonRpcProvider::new(server.url("/rpc"));
+
+        server.mock(|when, then| {
+            when.method(POST).path("/rpc").json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "starknet_getClassHashAt",
+                "params": {
+                    "block_id": "latest",
+                    "contract_address": "0x42",
+                },
+            }));
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": "0x666",
+            }));
+        });
+
+        let result = provider
+            .get_class_hash_at(FieldElement::from(0x42u64))
+            .await;
+
+        assert_ok_eq!(result, FieldElement::from(0x666u64));
+    }
+
+    #[tokio::test]
+    async fn get_transaction_status() {
+        let server = MockServer::start();
+        let provider = JsonRpcProvider::new(server.url("/rpc"));
+
+        server.mock(|when, then| {
+            when.method(POST).path("/rpc").json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "starknet_getTransactionReceipt",
+                "params": { "transaction_hash": "0x666" },
+            }));
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "transaction_hash": "0x666",
+                    "status": "REJECTED",
+                    "status_data": "Invalid nonce",
+                },
+            }));
+        });
+
+        let result = provider
+            .get_transaction_status(FieldElement::from(0x666u64))
+            .await;
+
+        assert_ok_eq!(
+            result,
+            TransactionStatus::Rejected {
+                reason: Some("Invalid nonce".to_string())
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_transaction_is_not_received() {
+        let server = MockServer::start();
+        let provider = JsonRpcProvider::new(server.url("/rpc"));
+
+        server.mock(|when, then| {
+            when.method(POST).path("/rpc");
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "error": { "code": 25, "message": "Transaction hash not found" },
+            }));
+        });
+
+        let result = provider
+            .get_transaction_status(FieldElement::from(0x666u64))
+            .await;
+
+        assert_ok_eq!(result, TransactionStatus::NotReceived);
+    }
+
+    fn invoke_transaction() -> InvokeTransaction {
+        InvokeTransaction {
+            sender_address: FieldElement::from(0x42u64),
+            calldata: vec![FieldElement::ONE],
+            max_fee: FieldElement::from(0x100u64),
+            signature: vec![FieldElement::from(2u64), FieldElement::from(3u64)],
+            nonce: FieldElement::from(7u64),
+        }
+    }
+
+    #[tokio::test]
+    async fn estimate_fee() {
+        let server = MockServer::start();
+        let provider = JsonRpcProvider::new(server.url("/rpc"));
+
+        server.mock(|when, then| {
+            when.method(POST).path("/rpc").json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "starknet_estimateFee",
+                "params": {
+                    "request": {
+                        "type": "INVOKE",
+                        "version": "0x1",
+                        "sender_address": "0x42",
+                        "calldata": ["0x1"],
+                        "max_fee": "0x100",
+                        "signature": ["0x2", "0x3"],
+                        "nonce": "0x7",
+                    },
+                    "block_id": "latest",
+                },
+            }));
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "gas_consumed": "0x10",
+                    "gas_price": "0x10",
+                    "overall_fee": "0x100",
+                },
+            }));
+        });
+
+        let result = provider.estimate_fee(&invoke_transaction()).await;
+
+        assert_ok_eq!(result, 0x100);
+    }
+
+    #[tokio::test]
+    async fn add_invoke_transaction() {
+        let server = MockServer::start();
+        let provider = JsonRpcProvider::new(server.url("/rpc"));
+
+        let rpc_mock = server.mock(|when, then| {
+            when.method(POST).path("/rpc").json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "starknet_addInvokeTransaction",
+                "params": {
+                    "invoke_transaction": {
+                        "type": "INVOKE",
+                        "version": "0x1",
+                        "sender_address": "0x42",
+                        "calldata": ["0x1"],
+                        "max_fee": "0x100",
+                        "signature": ["0x2", "0x3"],
+                        "nonce": "0x7",
+                    },
+                },
+            }));
+            then.status(200).json_body(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": { "transaction_hash": "0x666" },
+            }));
+        });
+
+        let result = provider.add_invoke_transaction(&invoke_transaction()).await;
+
+        rpc_mock.assert();
+        assert_ok_eq!(result, FieldElement::from(0x666u64));
+    }
+}
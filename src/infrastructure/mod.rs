@@ -1,7 +1,20 @@
+mod contributor_id;
+pub mod discord_client;
 mod errors;
 pub mod github_client;
+pub mod gitlab_client;
+pub mod in_memory_authorization_state_store;
+pub mod in_memory_idempotency_store;
+pub mod in_memory_identity_session_store;
+pub mod in_memory_nonce_store;
+mod json_rpc_provider;
+pub mod public_key_cache;
 mod registry_client;
+pub mod rule_based_eligibility_policy;
+pub mod sqlite_registration_repository;
+mod starknet_account;
 pub mod starknet_client;
+mod starknet_provider;
 
 pub use registry_client::Signature as StarknetSignature;
 pub use registry_client::SignedData as StarknetSignedData;
@@ -0,0 +1,152 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+use starknet::core::types::FieldElement;
+
+pub const DEFAULT_CAPACITY: usize = 10_000;
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60);
+
+struct CachedPublicKey {
+    public_key: FieldElement,
+    expires_at: SystemTime,
+    /// Order of insertion, telling the oldest key apart
+    insertion: u64,
+}
+
+#[derive(Default)]
+struct CachedPublicKeys {
+    public_keys: HashMap<FieldElement, CachedPublicKey>,
+    insertions: u64,
+}
+
+/// Public keys of accounts, by account address. Accounts can change their key at any time,
+/// so keys are only kept for `ttl`, and the oldest key is evicted once `capacity` keys are cached.
+pub struct PublicKeyCache {
+    capacity: usize,
+    ttl: Duration,
+    cache: Mutex<CachedPublicKeys>,
+}
+
+impl PublicKeyCache {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        PublicKeyCache {
+            capacity,
+            ttl,
+            cache: Mutex::new(CachedPublicKeys::default()),
+        }
+    }
+
+    pub fn get(&self, account_address: &FieldElement) -> Option<FieldElement> {
+        let mut cache = self.cache.lock().unwrap();
+        let cached = cache.public_keys.get(account_address)?;
+        if cached.expires_at > SystemTime::now() {
+            return Some(cached.public_key);
+        }
+
+        cache.public_keys.remove(account_address);
+        None
+    }
+
+    pub fn insert(&self, account_address: FieldElement, public_key: FieldElement) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let now = SystemTime::now();
+        let mut cache = self.cache.lock().unwrap();
+        if cache.public_keys.len() >= self.capacity
+            && !cache.public_keys.contains_key(&account_address)
+        {
+            // forget about expired keys first, then about the oldest one
+            cache
+                .public_keys
+                .retain(|_, cached| cached.expires_at > now);
+            if cache.public_keys.len() >= self.capacity {
+                let oldest = cache
+                    .public_keys
+                    .iter()
+                    .min_by_key(|(_, cached)| cached.insertion)
+                    .map(|(address, _)| *address);
+                if let Some(oldest) = oldest {
+                    cache.public_keys.remove(&oldest);
+                }
+            }
+        }
+
+        cache.insertions += 1;
+        let insertion = cache.insertions;
+        cache.public_keys.insert(
+            account_address,
+            CachedPublicKey {
+                public_key,
+                expires_at: now + self.ttl,
+                insertion,
+            },
+        );
+    }
+
+    pub fn remove(&self, account_address: &FieldElement) {
+        self.cache
+            .lock()
+            .unwrap()
+            .public_keys
+            .remove(account_address);
+    }
+}
+
+impl Default for PublicKeyCache {
+    fn default() -> Self {
+        PublicKeyCache::new(DEFAULT_CAPACITY, DEFAULT_TTL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use claim::{assert_none, assert_some_eq};
+    use starknet::core::types::FieldElement;
+
+    use super::PublicKeyCache;
+
+    #[test]
+    fn cached_public_key_is_returned() {
+        let cache = PublicKeyCache::new(2, Duration::from_secs(60));
+
+        cache.insert(FieldElement::ONE, FieldElement::from(42u64));
+
+        assert_some_eq!(cache.get(&FieldElement::ONE), FieldElement::from(42u64));
+        assert_none!(cache.get(&FieldElement::from(2u64)));
+    }
+
+    #[test]
+    fn expired_public_key_is_forgotten() {
+        let cache = PublicKeyCache::new(2, Duration::ZERO);
+
+        cache.insert(FieldElement::ONE, FieldElement::from(42u64));
+
+        assert_none!(cache.get(&FieldElement::ONE));
+    }
+
+    #[test]
+    fn oldest_public_key_is_evicted_when_full() {
+        let cache = PublicKeyCache::new(2, Duration::from_secs(60));
+
+        cache.insert(FieldElement::ONE, FieldElement::from(42u64));
+        cache.insert(FieldElement::from(2u64), FieldElement::from(43u64));
+        cache.insert(FieldElement::from(3u64), FieldElement::from(44u64));
+
+        assert_none!(cache.get(&FieldElement::ONE));
+        assert_some_eq!(
+            cache.get(&FieldElement::from(2u64)),
+            FieldElement::from(43u64)
+        );
+        assert_some_eq!(
+            cache.get(&FieldElement::from(3u64)),
+            FieldElement::from(44u64)
+        );
+    }
+}
@@ -1,42 +1,337 @@
 use starknet::{
-    accounts::{Account, AccountCall, Call},
+    accounts::Call,
     core::{
-        types::{BlockId, FieldElement, InvokeFunctionTransactionRequest},
+        crypto::{compute_hash_on_elements, ecdsa_verify, Signature as EcdsaSignature},
+        types::FieldElement,
         utils::get_selector_from_name,
     },
-    providers::Provider,
 };
 
 use crate::domain::{
     errors::{RegistryError, SignatureError},
     services::onchain_registry::OnChainRegistry,
-    value_objects::Identity,
+    value_objects::{Identity, Nonce, SignedOperation, TransactionStatus},
 };
 
-use super::starknet_client::StarkNetClient;
+use super::{
+    starknet_client::{SignatureVerification, StarkNetClient},
+    starknet_provider::StarkNetProviderError,
+};
 
-/// Stark ECDSA signature
-#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
-pub struct Signature {
-    /// The `r` value of a signature
-    pub r: FieldElement,
-    /// The `s` value of a signature
-    pub s: FieldElement,
-}
+/// Signature of an account, as checked by its `is_valid_signature` entry point.
+/// Its layout is up to the account implementation: `[r, s]` for a Stark ECDSA signature,
+/// longer arrays for multisig, hardware signer or guardian based accounts.
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord, Hash)]
+pub struct Signature(pub Vec<FieldElement>);
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, PartialOrd, Ord, Hash)]
 pub struct SignedData {
     pub hash: FieldElement,
     pub signature: Signature,
 }
 
-// This is need to be able to use a FieldElement as a ContributorId
-impl From<Identity> for FieldElement {
+/// Identifier of a contributor in the badge registry.
+/// Each identity provider has its own registry entry points,
+/// and its ids are namespaced in the encoded value (see [`super::contributor_id`]).
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct ContributorId {
+    provider: &'static str,
+    value: FieldElement,
+}
+
+impl From<Identity> for ContributorId {
     fn from(identity: Identity) -> Self {
-        match identity {
-            Identity::GitHubId(github_id) => FieldElement::from(github_id.0),
+        ContributorId {
+            provider: identity.provider(),
+            value: (&identity).into(),
+        }
+    }
+}
+
+impl ContributorId {
+    /// Name of the registry entry point performing `operation` for the identity provider.
+    /// There is no migration entry point, its name only identifies signed migration messages.
+    fn entry_point(&self, operation: SignedOperation) -> String {
+        match operation {
+            SignedOperation::Registration => format!("register_{}_identifier", self.provider),
+            SignedOperation::Unregistration => format!("unregister_{}_identifier", self.provider),
+            SignedOperation::Migration => format!("migrate_{}_identifier", self.provider),
+        }
+    }
+
+    fn registered_account_entry_point(&self) -> String {
+        format!("get_{}_identifier_account", self.provider)
+    }
+}
+
+impl From<Nonce> for FieldElement {
+    fn from(nonce: Nonce) -> Self {
+        let mut bytes = [0u8; 32];
+        bytes[16..].copy_from_slice(&nonce.0.to_be_bytes());
+        // a 128 bits value always fits in a field element
+        FieldElement::from_bytes_be(&bytes).unwrap()
+    }
+}
+
+/// Calldata of `is_valid_signature`: the hash, then the signature array prefixed by its length.
+fn signature_calldata(signed_data: SignedData) -> Vec<FieldElement> {
+    let signature = signed_data.signature.0;
+    let mut calldata = Vec::with_capacity(signature.len() + 2);
+    calldata.push(signed_data.hash);
+    calldata.push(FieldElement::from(signature.len() as u64));
+    calldata.extend(signature);
+    calldata
+}
+
+/// `'VALID'` short string, returned by SNIP-6 accounts when a signature is valid
+fn valid_short_string() -> FieldElement {
+    FieldElement::from_hex_be("0x56414c4944").unwrap()
+}
+
+/// Interprets the value returned by `is_valid_signature`.
+/// Legacy accounts revert on invalid signatures, and return nothing or `TRUE` otherwise.
+/// SNIP-6 accounts return `'VALID'`, or 0 when the signature is invalid.
+fn check_signature_result(result: &[FieldElement]) -> Result<(), SignatureError> {
+    match result {
+        [] => Ok(()),
+        [value] if *value == FieldElement::ONE || *value == valid_short_string() => Ok(()),
+        [value] => Err(SignatureError::InvalidSignature(
+            format!("account rejected the signature with {:#x}", value).into(),
+        )),
+        values => Err(SignatureError::InvalidSignature(
+            format!(
+                "unexpected is_valid_signature result of {} values",
+                values.len()
+            )
+            .into(),
+        )),
+    }
+}
+
+/// Checks that the class of the signing account is one of `allowed_class_hashes`, if any.
+fn check_account_class(
+    allowed_class_hashes: &[FieldElement],
+    class_hash: FieldElement,
+) -> Result<(), SignatureError> {
+    if allowed_class_hashes.is_empty() || allowed_class_hashes.contains(&class_hash) {
+        Ok(())
+    } else {
+        Err(SignatureError::UnsupportedAccount(format!(
+            "{:#x}",
+            class_hash
+        )))
+    }
+}
+
+fn class_hash_error(error: StarkNetProviderError) -> SignatureError {
+    match error {
+        StarkNetProviderError::ContractNotFound => SignatureError::AccountNotDeployed,
+        e => SignatureError::Provider(Box::new(e)),
+    }
+}
+
+/// Tells apart accounts rejecting the signature by reverting, undeployed accounts
+/// and failures to reach the provider.
+fn signature_call_error(error: StarkNetProviderError) -> SignatureError {
+    match error {
+        StarkNetProviderError::ContractNotFound => SignatureError::AccountNotDeployed,
+        StarkNetProviderError::Contract(e) => SignatureError::InvalidSignature(e),
+        e => SignatureError::Provider(Box::new(e)),
+    }
+}
+
+impl StarkNetClient {
+    /// Hash of the message a user must sign to perform `operation` on `user_id` and `account_address`.
+    ///
+    /// It binds the signature to the registry entry point, the account, the contributor id,
+    /// the chain, the registry contract and a server-issued nonce, so that it cannot be replayed.
+    pub fn message_hash(
+        &self,
+        operation: SignedOperation,
+        account_address: FieldElement,
+        user_id: ContributorId,
+        nonce: Nonce,
+    ) -> FieldElement {
+        compute_hash_on_elements(&[
+            get_selector_from_name(&user_id.entry_point(operation)).unwrap(),
+            account_address,
+            user_id.value,
+            self.chain_id,
+            self.badge_registry_address,
+            nonce.into(),
+        ])
+    }
+
+    /// Hash of the message both accounts sign to move `user_id` from `old_account_address`
+    /// to `new_account_address`.
+    ///
+    /// It commits to both accounts, so that a signature of the old account cannot be reused
+    /// to move the contributor id to another account.
+    pub fn migration_message_hash(
+        &self,
+        old_account_address: FieldElement,
+        new_account_address: FieldElement,
+        user_id: ContributorId,
+        nonce: Nonce,
+    ) -> FieldElement {
+        compute_hash_on_elements(&[
+            get_selector_from_name(&user_id.entry_point(SignedOperation::Migration)).unwrap(),
+            old_account_address,
+            new_account_address,
+            user_id.value,
+            self.chain_id,
+            self.badge_registry_address,
+            nonce.into(),
+        ])
+    }
+
+    /// Checks that `signed_data` signs `expected_hash`, with a valid signature of `signer_address`.
+    async fn check_signed_hash(
+        &self,
+        signed_data: SignedData,
+        expected_hash: FieldElement,
+        signer_address: FieldElement,
+    ) -> Result<(), SignatureError> {
+        if signed_data.hash != expected_hash {
+            return Err(SignatureError::UnexpectedHash);
+        }
+
+        if self.signature_verification == SignatureVerification::OffChain {
+            if let Some(result) = self
+                .verify_signature_off_chain(&signed_data, signer_address)
+                .await
+            {
+                return result;
+            }
+        }
+
+        self.verify_account(signer_address).await?;
+        self.verify_signature(signed_data, signer_address).await
+    }
+
+    /// Rejects undeployed accounts, and contracts that are not one of the allowed account classes.
+    async fn verify_account(&self, account_address: FieldElement) -> Result<(), SignatureError> {
+        if self.account_class_hashes.is_empty() {
+            return Ok(());
+        }
+
+        let class_hash = self
+            .provider
+            .get_class_hash_at(account_address)
+            .await
+            .map_err(class_hash_error)?;
+
+        check_account_class(&self.account_class_hashes, class_hash)
+    }
+
+    /// Reads the public key of the account through `get_public_key`, and caches it.
+    /// Unsupported accounts and accounts without `get_public_key` have none.
+    async fn fetch_public_key(&self, account_address: FieldElement) -> Option<FieldElement> {
+        self.verify_account(account_address).await.ok()?;
+
+        let call_result = self
+            .provider
+            .call_contract(
+                account_address,
+                get_selector_from_name("get_public_key").unwrap(),
+                vec![],
+            )
+            .await
+            .ok()?;
+
+        let public_key = match call_result.as_slice() {
+            [public_key] => *public_key,
+            _ => return None,
+        };
+        self.public_keys.insert(account_address, public_key);
+        Some(public_key)
+    }
+
+    /// Verifies a Stark ECDSA signature locally, against the public key of the account.
+    /// Returns `None` when the signature must be verified on-chain: signatures that are not `[r, s]`,
+    /// and accounts without a known public key. Signatures that do not match a cached public key
+    /// are checked once more against the current key of the account, which may have changed it.
+    async fn verify_signature_off_chain(
+        &self,
+        signed_data: &SignedData,
+        account_address: FieldElement,
+    ) -> Option<Result<(), SignatureError>> {
+        let signature = match signed_data.signature.0.as_slice() {
+            [r, s] => EcdsaSignature { r: *r, s: *s },
+            _ => return None,
+        };
+
+        if let Some(public_key) = self.public_keys.get(&account_address) {
+            match ecdsa_verify(&public_key, &signed_data.hash, &signature) {
+                Ok(true) => return Some(Ok(())),
+                Ok(false) => self.public_keys.remove(&account_address),
+                Err(e) => return Some(Err(SignatureError::InvalidSignature(Box::new(e)))),
+            }
+        }
+
+        let public_key = self.fetch_public_key(account_address).await?;
+        match ecdsa_verify(&public_key, &signed_data.hash, &signature) {
+            Ok(true) => Some(Ok(())),
+            Ok(false) => Some(Err(SignatureError::InvalidSignature(
+                "signature does not match the public key of the account".into(),
+            ))),
+            Err(e) => Some(Err(SignatureError::InvalidSignature(Box::new(e)))),
+        }
+    }
+
+    async fn verify_signature(
+        &self,
+        signed_data: SignedData,
+        account_address: FieldElement,
+    ) -> Result<(), SignatureError> {
+        let call_result = self
+            .provider
+            .call_contract(
+                account_address,
+                get_selector_from_name("is_valid_signature").unwrap(),
+                signature_calldata(signed_data),
+            )
+            .await
+            .map_err(signature_call_error)?;
+
+        check_signature_result(&call_result)
+    }
+
+    fn registry_call(
+        &self,
+        operation: SignedOperation,
+        user_account_address: FieldElement,
+        user_id: ContributorId,
+    ) -> Call {
+        Call {
+            to: self.badge_registry_address,
+            selector: get_selector_from_name(&user_id.entry_point(operation)).unwrap(),
+            calldata: vec![user_account_address, user_id.value],
         }
     }
+
+    /// Key of the 2D nonce dedicated to `user_id` in this registry. Registries managed by the
+    /// same account have their own keys, so that transactions for the same user do not race.
+    fn nonce_key(&self, user_id: ContributorId) -> FieldElement {
+        compute_hash_on_elements(&[self.badge_registry_address, user_id.value])
+    }
+
+    /// Sends `calls` in a single transaction, using the 2D nonce dedicated to `user_id`.
+    async fn send_registry_calls(
+        &self,
+        calls: &[Call],
+        user_id: ContributorId,
+    ) -> Result<FieldElement, RegistryError> {
+        let nonce = self
+            .get_2d_nonce(self.nonce_key(user_id))
+            .await
+            .map_err(|e| RegistryError::Nonce(Box::new(e)))?;
+
+        self.account
+            .execute(calls, nonce)
+            .await
+            .map_err(RegistryError::Transaction)
+    }
 }
 
 #[rocket::async_trait]
@@ -44,33 +339,62 @@ impl OnChainRegistry for StarkNetClient {
     type SignedData = SignedData;
     type AccountAddress = FieldElement;
     type TransactionHash = FieldElement;
-    type ContributorId = FieldElement;
+    type ContributorId = ContributorId;
 
     async fn check_signature(
         &self,
         signed_data: SignedData,
         account_address: Self::AccountAddress,
+        user_id: Self::ContributorId,
+        nonce: Nonce,
+        operation: SignedOperation,
     ) -> Result<(), SignatureError> {
-        self.provider
+        let expected_hash = self.message_hash(operation, account_address, user_id, nonce);
+        self.check_signed_hash(signed_data, expected_hash, account_address)
+            .await
+    }
+
+    async fn check_migration_signature(
+        &self,
+        signed_data: SignedData,
+        signer_address: Self::AccountAddress,
+        old_account_address: Self::AccountAddress,
+        new_account_address: Self::AccountAddress,
+        user_id: Self::ContributorId,
+        nonce: Nonce,
+    ) -> Result<(), SignatureError> {
+        let expected_hash =
+            self.migration_message_hash(old_account_address, new_account_address, user_id, nonce);
+        self.check_signed_hash(signed_data, expected_hash, signer_address)
+            .await
+    }
+
+    async fn get_registered_account(
+        &self,
+        user_id: Self::ContributorId,
+    ) -> Result<Option<Self::AccountAddress>, RegistryError> {
+        let call_result = self
+            .provider
             .call_contract(
-                InvokeFunctionTransactionRequest {
-                    contract_address: account_address,
-                    entry_point_selector: get_selector_from_name("is_valid_signature").unwrap(),
-                    calldata: vec![
-                        signed_data.hash,
-                        FieldElement::from(2u64),
-                        signed_data.signature.r,
-                        signed_data.signature.s,
-                    ],
-                    signature: vec![],
-                    max_fee: FieldElement::ZERO,
-                },
-                BlockId::Latest,
+                self.badge_registry_address,
+                get_selector_from_name(&user_id.registered_account_entry_point()).unwrap(),
+                vec![user_id.value],
             )
             .await
-            .map_err(|e| SignatureError::InvalidSignature(Box::new(e)))?;
-
-        Ok(())
+            .map_err(|e| RegistryError::Query(Box::new(e)))?;
+
+        match call_result[..] {
+            // the registry returns a zero address for unregistered contributors
+            [account_address] if account_address == FieldElement::ZERO => Ok(None),
+            [account_address] => Ok(Some(account_address)),
+            _ => Err(RegistryError::Query(
+                format!(
+                    "Invalid response length, expected 1, got {}",
+                    call_result.len()
+                )
+                .into(),
+            )),
+        }
     }
 
     async fn register_contributor(
@@ -78,22 +402,57 @@ impl OnChainRegistry for StarkNetClient {
         user_account_address: Self::AccountAddress,
         user_id: Self::ContributorId,
     ) -> Result<Self::TransactionHash, RegistryError> {
-        let nonce = self
-            .get_2d_nonce(user_id)
-            .await
-            .map_err(|e| RegistryError::Nonce(Box::new(e)))?;
+        self.send_registry_calls(
+            &[self.registry_call(SignedOperation::Registration, user_account_address, user_id)],
+            user_id,
+        )
+        .await
+    }
 
-        self.account
-            .execute(&[Call {
-                to: self.badge_registry_address,
-                selector: get_selector_from_name("register_github_identifier").unwrap(),
-                calldata: vec![user_account_address, user_id],
-            }])
-            .nonce(nonce)
-            .send()
+    async fn unregister_contributor(
+        &self,
+        user_account_address: Self::AccountAddress,
+        user_id: Self::ContributorId,
+    ) -> Result<Self::TransactionHash, RegistryError> {
+        self.send_registry_calls(
+            &[self.registry_call(
+                SignedOperation::Unregistration,
+                user_account_address,
+                user_id,
+            )],
+            user_id,
+        )
+        .await
+    }
+
+    async fn migrate_contributor(
+        &self,
+        old_account_address: Self::AccountAddress,
+        new_account_address: Self::AccountAddress,
+        user_id: Self::ContributorId,
+    ) -> Result<Self::TransactionHash, RegistryError> {
+        self.send_registry_calls(
+            &[
+                self.registry_call(
+                    SignedOperation::Unregistration,
+                    old_account_address,
+                    user_id,
+                ),
+                self.registry_call(SignedOperation::Registration, new_account_address, user_id),
+            ],
+            user_id,
+        )
+        .await
+    }
+
+    async fn get_transaction_status(
+        &self,
+        transaction_hash: Self::TransactionHash,
+    ) -> Result<TransactionStatus, RegistryError> {
+        self.provider
+            .get_transaction_status(transaction_hash)
             .await
-            .map_err(|e| RegistryError::Transaction(Box::new(e)))
-            .map(|transaction_result| transaction_result.transaction_hash)
+            .map_err(|e| RegistryError::Status(Box::new(e)))
     }
 }
 
@@ -103,13 +462,22 @@ mod tests {
     use dotenv::dotenv;
     use rand::prelude::*;
     use rocket::tokio;
-    use starknet::core::types::FieldElement;
+    use starknet::{core::types::FieldElement, signers::SigningKey};
 
     use crate::{
-        domain::{errors::SignatureError, services::onchain_registry::OnChainRegistry},
+        domain::{
+            errors::SignatureError,
+            services::onchain_registry::OnChainRegistry,
+            value_objects::{Identity, Nonce, SignedOperation},
+        },
         infrastructure::{
-            registry_client::{Signature, SignedData},
-            starknet_client::{StarkNetChain, StarkNetClient},
+            registry_client::{
+                check_account_class, check_signature_result, signature_calldata, ContributorId,
+                Signature, SignedData,
+            },
+            starknet_client::{
+                SequencerGateway, SignatureVerification, StarkNetChain, StarkNetClient,
+            },
         },
     };
 
@@ -124,6 +492,14 @@ mod tests {
     const BAD_SIGNATURE_R: &str =
         "0x000049b21dd8714eaf5a1b480d8ede84d2230d1763cfe06762d8a117490000";
 
+    fn github_user(github_id: u64) -> ContributorId {
+        Identity::GitHubId(github_id.into()).into()
+    }
+
+    fn testnet_gateway() -> SequencerGateway {
+        StarkNetChain::Testnet.default_gateway().unwrap()
+    }
+
     fn new_test_client() -> StarkNetClient {
         dotenv().ok();
         let admin_account = std::env::var("STARKNET_ACCOUNT").unwrap();
@@ -134,12 +510,304 @@ mod tests {
             admin_private_key.as_str(),
             REGISTRY_ADDRESS,
             StarkNetChain::Testnet,
+            testnet_gateway(),
         )
     }
 
+    #[tokio::test]
+    async fn check_signature_rejects_unexpected_hash() {
+        let client = StarkNetClient::new(
+            "0x1",
+            "0x1",
+            "0x2",
+            StarkNetChain::Testnet,
+            testnet_gateway(),
+        );
+
+        let address = FieldElement::from_hex_be(ANYONE_TEST_ACCOUNT).unwrap();
+        let user_id = github_user(42);
+        let nonce = Nonce::from(1);
+        let hash = client.message_hash(
+            SignedOperation::Registration,
+            address,
+            github_user(43),
+            nonce,
+        );
+
+        let result = client
+            .check_signature(
+                SignedData {
+                    hash,
+                    signature: Signature(vec![
+                        FieldElement::from_hex_be(SIGNATURE_R).unwrap(),
+                        FieldElement::from_hex_be(SIGNATURE_S).unwrap(),
+                    ]),
+                },
+                address,
+                user_id,
+                nonce,
+                SignedOperation::Registration,
+            )
+            .await;
+
+        assert!(matches!(result, Err(SignatureError::UnexpectedHash)));
+    }
+
+    #[test]
+    fn signature_calldata_holds_the_whole_signature_array() {
+        let hash = FieldElement::from_hex_be(HASH).unwrap();
+        let signer = FieldElement::from_hex_be(SIGNATURE_R).unwrap();
+        let guardian = FieldElement::from_hex_be(SIGNATURE_S).unwrap();
+
+        assert_eq!(
+            signature_calldata(SignedData {
+                hash,
+                signature: Signature(vec![signer, guardian, signer, guardian]),
+            }),
+            vec![
+                hash,
+                FieldElement::from(4u64),
+                signer,
+                guardian,
+                signer,
+                guardian
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn off_chain_verification_uses_the_cached_public_key() {
+        let client = StarkNetClient::new(
+            "0x1",
+            "0x1",
+            "0x2",
+            StarkNetChain::Testnet,
+            testnet_gateway(),
+        )
+        .with_signature_verification(SignatureVerification::OffChain);
+        let signing_key = SigningKey::from_secret_scalar(FieldElement::from(42u64));
+
+        let address = FieldElement::from_hex_be(ANYONE_TEST_ACCOUNT).unwrap();
+        client
+            .public_keys
+            .insert(address, signing_key.verifying_key().scalar());
+
+        let user_id = github_user(42);
+        let nonce = Nonce::from(1);
+        let hash = client.message_hash(SignedOperation::Registration, address, user_id, nonce);
+        let signature = signing_key.sign(&hash).unwrap();
+
+        let result = client
+            .check_signature(
+                SignedData {
+                    hash,
+                    signature: Signature(vec![signature.r, signature.s]),
+                },
+                address,
+                user_id,
+                nonce,
+                SignedOperation::Registration,
+            )
+            .await;
+
+        assert!(result.is_ok(), "{}", result.err().unwrap());
+    }
+
+    #[test]
+    fn legacy_signature_results_are_valid() {
+        assert!(check_signature_result(&[]).is_ok());
+        assert!(check_signature_result(&[FieldElement::ONE]).is_ok());
+    }
+
+    #[test]
+    fn snip6_signature_results_are_interpreted() {
+        let valid = FieldElement::from_hex_be("0x56414c4944").unwrap();
+
+        assert!(check_signature_result(&[valid]).is_ok());
+        assert!(matches!(
+            check_signature_result(&[FieldElement::ZERO]),
+            Err(SignatureError::InvalidSignature(_))
+        ));
+    }
+
+    #[test]
+    fn unexpected_signature_results_are_invalid() {
+        assert!(matches!(
+            check_signature_result(&[FieldElement::ONE, FieldElement::ONE]),
+            Err(SignatureError::InvalidSignature(_))
+        ));
+    }
+
+    #[test]
+    fn any_account_class_is_supported_without_allow_list() {
+        assert!(check_account_class(&[], FieldElement::from(42u64)).is_ok());
+    }
+
+    #[test]
+    fn account_class_must_be_in_allow_list() {
+        let allowed = [FieldElement::from(1u64), FieldElement::from(2u64)];
+
+        assert!(check_account_class(&allowed, FieldElement::from(2u64)).is_ok());
+        assert!(matches!(
+            check_account_class(&allowed, FieldElement::from(42u64)),
+            Err(SignatureError::UnsupportedAccount(class_hash)) if class_hash == "0x2a"
+        ));
+    }
+
+    #[test]
+    fn message_hash_is_bound_to_operation_registry_chain_and_nonce() {
+        let client = StarkNetClient::new(
+            "0x1",
+            "0x1",
+            "0x2",
+            StarkNetChain::Testnet,
+            testnet_gateway(),
+        );
+        let other_registry_client = StarkNetClient::new(
+            "0x1",
+            "0x1",
+            "0x3",
+            StarkNetChain::Testnet,
+            testnet_gateway(),
+        );
+        let other_chain_client = StarkNetClient::new(
+            "0x1",
+            "0x1",
+            "0x2",
+            StarkNetChain::Mainnet,
+            testnet_gateway(),
+        );
+
+        let address = FieldElement::from_hex_be(ANYONE_TEST_ACCOUNT).unwrap();
+        let user_id = github_user(42);
+        let nonce = Nonce::from(1);
+        let registration = SignedOperation::Registration;
+        let hash = client.message_hash(registration, address, user_id, nonce);
+
+        assert_eq!(
+            hash,
+            client.message_hash(registration, address, user_id, nonce)
+        );
+        assert_ne!(
+            hash,
+            client.message_hash(SignedOperation::Unregistration, address, user_id, nonce)
+        );
+        assert_ne!(
+            hash,
+            client.message_hash(registration, address, github_user(43), nonce)
+        );
+        assert_ne!(
+            hash,
+            client.message_hash(registration, address, user_id, Nonce::from(2))
+        );
+        assert_ne!(
+            hash,
+            other_registry_client.message_hash(registration, address, user_id, nonce)
+        );
+        assert_ne!(
+            hash,
+            other_chain_client.message_hash(registration, address, user_id, nonce)
+        );
+    }
+
+    #[test]
+    fn migration_message_hash_is_bound_to_both_accounts() {
+        let client = StarkNetClient::new(
+            "0x1",
+            "0x1",
+            "0x2",
+            StarkNetChain::Testnet,
+            testnet_gateway(),
+        );
+
+        let old_address = FieldElement::from_hex_be(ANYONE_TEST_ACCOUNT).unwrap();
+        let new_address = FieldElement::from(42u64);
+        let user_id = github_user(42);
+        let nonce = Nonce::from(1);
+        let hash = client.migration_message_hash(old_address, new_address, user_id, nonce);
+
+        assert_eq!(
+            hash,
+            client.migration_message_hash(old_address, new_address, user_id, nonce)
+        );
+        assert_ne!(
+            hash,
+            client.migration_message_hash(old_address, FieldElement::from(43u64), user_id, nonce)
+        );
+        assert_ne!(
+            hash,
+            client.migration_message_hash(new_address, old_address, user_id, nonce)
+        );
+        assert_ne!(
+            hash,
+            client.migration_message_hash(old_address, new_address, github_user(43), nonce)
+        );
+        assert_ne!(
+            hash,
+            client.migration_message_hash(old_address, new_address, user_id, Nonce::from(2))
+        );
+        assert_ne!(
+            hash,
+            client.message_hash(SignedOperation::Migration, old_address, user_id, nonce)
+        );
+    }
+
+    #[test]
+    fn nonce_key_is_bound_to_registry_and_user() {
+        let client = StarkNetClient::new(
+            "0x1",
+            "0x1",
+            "0x2",
+            StarkNetChain::Testnet,
+            testnet_gateway(),
+        );
+        let other_registry_client = StarkNetClient::new(
+            "0x1",
+            "0x1",
+            "0x3",
+            StarkNetChain::Testnet,
+            testnet_gateway(),
+        );
+
+        let key = client.nonce_key(github_user(42));
+
+        assert_eq!(key, client.nonce_key(github_user(42)));
+        assert_ne!(key, client.nonce_key(github_user(43)));
+        assert_ne!(key, other_registry_client.nonce_key(github_user(42)));
+    }
+
+    #[test]
+    fn message_hash_is_bound_to_identity_provider() {
+        let client = StarkNetClient::new(
+            "0x1",
+            "0x1",
+            "0x2",
+            StarkNetChain::Testnet,
+            testnet_gateway(),
+        );
+
+        let address = FieldElement::from_hex_be(ANYONE_TEST_ACCOUNT).unwrap();
+        let nonce = Nonce::from(1);
+
+        assert_ne!(
+            client.message_hash(
+                SignedOperation::Registration,
+                address,
+                github_user(42),
+                nonce
+            ),
+            client.message_hash(
+                SignedOperation::Registration,
+                address,
+                Identity::GitLabId(42.into()).into(),
+                nonce
+            )
+        );
+    }
+
     #[ignore]
     #[tokio::test]
-    async fn check_signature_is_valid() {
+    async fn verify_signature_is_valid() {
         let client = new_test_client();
 
         let address = FieldElement::from_hex_be(ANYONE_TEST_ACCOUNT).unwrap();
@@ -148,13 +816,10 @@ mod tests {
         let signature_s = FieldElement::from_hex_be(SIGNATURE_S).unwrap();
 
         let result = client
-            .check_signature(
+            .verify_signature(
                 SignedData {
                     hash,
-                    signature: Signature {
-                        r: signature_r,
-                        s: signature_s,
-                    },
+                    signature: Signature(vec![signature_r, signature_s]),
                 },
                 address,
             )
@@ -165,7 +830,7 @@ mod tests {
 
     #[ignore]
     #[tokio::test]
-    async fn check_signature_is_not_valid() {
+    async fn verify_signature_is_not_valid() {
         let client = new_test_client();
 
         let address = FieldElement::from_hex_be(ANYONE_TEST_ACCOUNT).unwrap();
@@ -174,13 +839,10 @@ mod tests {
         let signature_s = FieldElement::from_hex_be(SIGNATURE_S).unwrap();
 
         let result = client
-            .check_signature(
+            .verify_signature(
                 SignedData {
                     hash,
-                    signature: Signature {
-                        r: signature_r,
-                        s: signature_s,
-                    },
+                    signature: Signature(vec![signature_r, signature_s]),
                 },
                 address,
             )
@@ -193,6 +855,7 @@ mod tests {
                     .to_string()
                     .contains("is invalid, with respect to the public key"))
             }
+            e => panic!("unexpected error: {}", e),
         }
     }
 
@@ -206,7 +869,7 @@ mod tests {
         let user_address = FieldElement::from(user_id - 42);
 
         let result = client
-            .register_contributor(user_address, FieldElement::from(user_id))
+            .register_contributor(user_address, github_user(user_id))
             .await;
         assert!(result.is_ok(), "{:#?}", result.err().unwrap());
 
@@ -229,7 +892,7 @@ mod tests {
         for _ in 0..5 {
             let user_address = FieldElement::from(user_id - 42);
             let transaction_result = client
-                .register_contributor(user_address, FieldElement::from(user_id))
+                .register_contributor(user_address, github_user(user_id))
                 .await;
             assert!(
                 transaction_result.is_ok(),
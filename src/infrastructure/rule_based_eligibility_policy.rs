@@ -0,0 +1,261 @@
+use std::time::{Duration, SystemTime};
+
+use chrono::DateTime;
+
+use crate::domain::{
+    errors::EligibilityError,
+    services::eligibility_policy::EligibilityPolicy,
+    value_objects::{Identity, IdentityProfile},
+};
+
+/// Rules an account must follow to be registered. The default rules accept every account.
+#[derive(Debug, Clone, Default)]
+pub struct EligibilityRules {
+    pub min_account_age: Duration,
+    pub min_public_repos: u64,
+    pub min_followers: u64,
+    /// Logins or ids of accounts that are always eligible
+    pub allowed_accounts: Vec<String>,
+    /// Logins or ids of accounts that are never eligible
+    pub denied_accounts: Vec<String>,
+}
+
+pub struct RuleBasedEligibilityPolicy {
+    rules: EligibilityRules,
+}
+
+impl RuleBasedEligibilityPolicy {
+    pub fn new(rules: EligibilityRules) -> Self {
+        RuleBasedEligibilityPolicy { rules }
+    }
+
+    fn account_age(profile: &IdentityProfile) -> Option<Duration> {
+        let created_at = DateTime::parse_from_rfc3339(profile.created_at.as_deref()?).ok()?;
+        Some(
+            SystemTime::now()
+                .duration_since(created_at.into())
+                .unwrap_or_default(),
+        )
+    }
+
+    /// Active accounts have either enough public repositories or enough followers.
+    /// Minimums left to zero are not taken into account.
+    fn check_activity(&self, profile: &IdentityProfile) -> Result<(), EligibilityError> {
+        let min_public_repos = self.rules.min_public_repos;
+        let min_followers = self.rules.min_followers;
+
+        let has_enough_repositories =
+            min_public_repos > 0 && profile.public_repos.unwrap_or_default() >= min_public_repos;
+        let has_enough_followers =
+            min_followers > 0 && profile.followers.unwrap_or_default() >= min_followers;
+
+        if has_enough_repositories || has_enough_followers {
+            Ok(())
+        } else if min_public_repos > 0 {
+            Err(EligibilityError::NotEnoughRepositories)
+        } else if min_followers > 0 {
+            Err(EligibilityError::NotEnoughFollowers)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Whether `accounts` holds the login (case insensitive) or the id of the user.
+fn is_listed(accounts: &[String], identity: &Identity, profile: &IdentityProfile) -> bool {
+    let user_id = identity.user_id().to_string();
+    accounts
+        .iter()
+        .any(|account| account.eq_ignore_ascii_case(&profile.login) || *account == user_id)
+}
+
+impl EligibilityPolicy for RuleBasedEligibilityPolicy {
+    fn check(
+        &self,
+        identity: &Identity,
+        profile: &IdentityProfile,
+    ) -> Result<(), EligibilityError> {
+        if is_listed(&self.rules.denied_accounts, identity, profile) {
+            return Err(EligibilityError::AccountDenied);
+        }
+        if is_listed(&self.rules.allowed_accounts, identity, profile) {
+            return Ok(());
+        }
+
+        if profile.suspended {
+            return Err(EligibilityError::AccountSuspended);
+        }
+
+        // accounts of unknown age are considered too recent
+        if !self.rules.min_account_age.is_zero()
+            && Self::account_age(profile).unwrap_or_default() < self.rules.min_account_age
+        {
+            return Err(EligibilityError::AccountTooRecent);
+        }
+
+        self.check_activity(profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::{SecondsFormat, Utc};
+    use claim::{assert_err_eq, assert_ok};
+
+    use super::{EligibilityRules, RuleBasedEligibilityPolicy};
+    use crate::domain::{
+        errors::EligibilityError,
+        services::eligibility_policy::EligibilityPolicy,
+        value_objects::{Identity, IdentityProfile},
+    };
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn profile_created(age: Duration) -> IdentityProfile {
+        let created_at = Utc::now() - chrono::Duration::from_std(age).unwrap();
+        IdentityProfile {
+            login: "octocat".to_string(),
+            name: None,
+            avatar_url: None,
+            created_at: Some(created_at.to_rfc3339_opts(SecondsFormat::Secs, true)),
+            public_repos: Some(8),
+            followers: Some(20),
+            suspended: false,
+        }
+    }
+
+    fn strict_rules() -> EligibilityRules {
+        EligibilityRules {
+            min_account_age: 30 * DAY,
+            min_public_repos: 1,
+            min_followers: 1,
+            ..Default::default()
+        }
+    }
+
+    fn github_user() -> Identity {
+        Identity::GitHubId(42.into())
+    }
+
+    #[test]
+    fn default_rules_accept_any_account() {
+        let policy = RuleBasedEligibilityPolicy::new(EligibilityRules::default());
+
+        let profile = IdentityProfile {
+            created_at: None,
+            public_repos: None,
+            followers: None,
+            ..profile_created(Duration::ZERO)
+        };
+
+        assert_ok!(policy.check(&github_user(), &profile));
+    }
+
+    #[test]
+    fn established_account_is_eligible() {
+        let policy = RuleBasedEligibilityPolicy::new(strict_rules());
+
+        assert_ok!(policy.check(&github_user(), &profile_created(365 * DAY)));
+    }
+
+    #[test]
+    fn recent_account_is_not_eligible() {
+        let policy = RuleBasedEligibilityPolicy::new(strict_rules());
+
+        assert_err_eq!(
+            policy.check(&github_user(), &profile_created(DAY)),
+            EligibilityError::AccountTooRecent
+        );
+    }
+
+    #[test]
+    fn account_of_unknown_age_is_not_eligible() {
+        let policy = RuleBasedEligibilityPolicy::new(strict_rules());
+
+        let profile = IdentityProfile {
+            created_at: None,
+            ..profile_created(365 * DAY)
+        };
+
+        assert_err_eq!(
+            policy.check(&github_user(), &profile),
+            EligibilityError::AccountTooRecent
+        );
+    }
+
+    #[test]
+    fn inactive_account_is_not_eligible() {
+        let policy = RuleBasedEligibilityPolicy::new(strict_rules());
+
+        let profile = IdentityProfile {
+            public_repos: Some(0),
+            ..profile_created(365 * DAY)
+        };
+        assert_ok!(policy.check(&github_user(), &profile));
+
+        let profile = IdentityProfile {
+            followers: None,
+            ..profile_created(365 * DAY)
+        };
+        assert_ok!(policy.check(&github_user(), &profile));
+
+        let profile = IdentityProfile {
+            public_repos: Some(0),
+            followers: None,
+            ..profile_created(365 * DAY)
+        };
+        assert_err_eq!(
+            policy.check(&github_user(), &profile),
+            EligibilityError::NotEnoughRepositories
+        );
+
+        let policy = RuleBasedEligibilityPolicy::new(EligibilityRules {
+            min_followers: 50,
+            ..Default::default()
+        });
+        assert_err_eq!(
+            policy.check(&github_user(), &profile_created(365 * DAY)),
+            EligibilityError::NotEnoughFollowers
+        );
+    }
+
+    #[test]
+    fn suspended_account_is_not_eligible() {
+        let policy = RuleBasedEligibilityPolicy::new(EligibilityRules::default());
+
+        let profile = IdentityProfile {
+            suspended: true,
+            ..profile_created(365 * DAY)
+        };
+
+        assert_err_eq!(
+            policy.check(&github_user(), &profile),
+            EligibilityError::AccountSuspended
+        );
+    }
+
+    #[test]
+    fn denied_account_is_not_eligible() {
+        let policy = RuleBasedEligibilityPolicy::new(EligibilityRules {
+            denied_accounts: vec!["OctoCat".to_string()],
+            ..Default::default()
+        });
+
+        assert_err_eq!(
+            policy.check(&github_user(), &profile_created(365 * DAY)),
+            EligibilityError::AccountDenied
+        );
+    }
+
+    #[test]
+    fn allowed_account_bypasses_other_rules() {
+        let policy = RuleBasedEligibilityPolicy::new(EligibilityRules {
+            allowed_accounts: vec!["42".to_string()],
+            ..strict_rules()
+        });
+
+        assert_ok!(policy.check(&github_user(), &profile_created(DAY)));
+    }
+}
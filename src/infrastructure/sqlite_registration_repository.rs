@@ -0,0 +1,549 @@
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use crate::domain::{
+    entities::{Registration, RegistrationStatus},
+    errors::RepositoryError,
+    services::registration_repository::RegistrationRepository,
+    value_objects::{Identity, IdentityProfile},
+};
+
+/// Schema migrations, applied in order. The index of the last applied one is kept in `user_version`.
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE registrations (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        status TEXT NOT NULL,
+        identity_provider TEXT,
+        identity_id TEXT,
+        account_address TEXT NOT NULL,
+        transaction_hash TEXT,
+        error_kind TEXT,
+        created_at INTEGER NOT NULL,
+        updated_at INTEGER NOT NULL
+    );
+    CREATE INDEX registrations_identity ON registrations (identity_provider, identity_id);
+    CREATE INDEX registrations_transaction_hash ON registrations (transaction_hash);",
+    "ALTER TABLE registrations ADD COLUMN profile_login TEXT;
+    ALTER TABLE registrations ADD COLUMN profile_name TEXT;
+    ALTER TABLE registrations ADD COLUMN profile_avatar_url TEXT;
+    ALTER TABLE registrations ADD COLUMN profile_created_at TEXT;",
+    "ALTER TABLE registrations ADD COLUMN registry TEXT;",
+];
+
+const SELECT_REGISTRATIONS: &str = "SELECT id, status, identity_provider, identity_id,
+    account_address, transaction_hash, error_kind, created_at, updated_at,
+    profile_login, profile_name, profile_avatar_url, profile_created_at
+    FROM registrations";
+
+/// Registrations of a badge registry. Views of the other registries of the same database
+/// are given by `for_registry`.
+#[derive(Clone)]
+pub struct SqliteRegistrationRepository {
+    connection: Arc<Mutex<Connection>>,
+    /// Name of the badge registry, `None` for the default one
+    registry: Option<String>,
+}
+
+impl SqliteRegistrationRepository {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, RepositoryError> {
+        Self::migrate(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, RepositoryError> {
+        Self::migrate(Connection::open_in_memory()?)
+    }
+
+    fn migrate(mut connection: Connection) -> Result<Self, RepositoryError> {
+        let version: i64 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            let transaction = connection.transaction()?;
+            transaction.execute_batch(migration)?;
+            transaction.pragma_update(None, "user_version", (index + 1) as i64)?;
+            transaction.commit()?;
+        }
+
+        Ok(SqliteRegistrationRepository {
+            connection: Arc::new(Mutex::new(connection)),
+            registry: None,
+        })
+    }
+
+    /// Registrations of the `registry` badge registry, stored in the same database.
+    /// Registrations are only looked up by identity or status within their registry.
+    pub fn for_registry(&self, registry: &str) -> Self {
+        SqliteRegistrationRepository {
+            connection: self.connection.clone(),
+            registry: Some(registry.to_string()),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl RegistrationRepository for SqliteRegistrationRepository {
+    async fn create(&self, account_address: String) -> Result<Registration, RepositoryError> {
+        // timestamps are stored with a millisecond precision
+        let now = from_timestamp(to_timestamp(SystemTime::now()));
+
+        let connection = self.connection.lock().unwrap();
+        connection.execute(
+            "INSERT INTO registrations (status, account_address, created_at, updated_at, registry)
+            VALUES (?1, ?2, ?3, ?3, ?4)",
+            params![
+                status_to_str(RegistrationStatus::Started),
+                account_address,
+                to_timestamp(now),
+                self.registry
+            ],
+        )?;
+
+        Ok(Registration {
+            id: connection.last_insert_rowid(),
+            status: RegistrationStatus::Started,
+            identity: None,
+            profile: None,
+            account_address,
+            transaction_hash: None,
+            error_kind: None,
+            created_at: now,
+            updated_at: now,
+        })
+    }
+
+    async fn save(&self, registration: &Registration) -> Result<(), RepositoryError> {
+        let (identity_provider, identity_id) = match &registration.identity {
+            Some(identity) => {
+                let (provider, id) = identity_to_columns(identity);
+                (Some(provider), Some(id))
+            }
+            None => (None, None),
+        };
+        let profile = registration.profile.as_ref();
+
+        self.connection.lock().unwrap().execute(
+            "UPDATE registrations SET status = ?2, identity_provider = ?3, identity_id = ?4,
+            account_address = ?5, transaction_hash = ?6, error_kind = ?7, updated_at = ?8,
+            profile_login = ?9, profile_name = ?10, profile_avatar_url = ?11, profile_created_at = ?12
+            WHERE id = ?1",
+            params![
+                registration.id,
+                status_to_str(registration.status),
+                identity_provider,
+                identity_id,
+                registration.account_address,
+                registration.transaction_hash,
+                registration.error_kind,
+                to_timestamp(registration.updated_at),
+                profile.map(|profile| &profile.login),
+                profile.and_then(|profile| profile.name.as_ref()),
+                profile.and_then(|profile| profile.avatar_url.as_ref()),
+                profile.and_then(|profile| profile.created_at.as_ref())
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    async fn find_by_identity(
+        &self,
+        identity: &Identity,
+    ) -> Result<Vec<Registration>, RepositoryError> {
+        let (provider, id) = identity_to_columns(identity);
+
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(&format!(
+            "{} WHERE identity_provider = ?1 AND identity_id = ?2 AND registry IS ?3 ORDER BY id",
+            SELECT_REGISTRATIONS
+        ))?;
+        let rows = statement
+            .query_map(params![provider, id, self.registry], RegistrationRow::read)?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        rows.into_iter().map(Registration::try_from).collect()
+    }
+
+    async fn find_by_transaction_hash(
+        &self,
+        transaction_hash: &str,
+    ) -> Result<Option<Registration>, RepositoryError> {
+        let row = self
+            .connection
+            .lock()
+            .unwrap()
+            .query_row(
+                &format!(
+                    "{} WHERE transaction_hash = ?1 ORDER BY id DESC LIMIT 1",
+                    SELECT_REGISTRATIONS
+                ),
+                params![transaction_hash],
+                RegistrationRow::read,
+            )
+            .optional()?;
+
+        row.map(Registration::try_from).transpose()
+    }
+
+    async fn find_by_status(
+        &self,
+        status: RegistrationStatus,
+    ) -> Result<Vec<Registration>, RepositoryError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(&format!(
+            "{} WHERE status = ?1 AND registry IS ?2 ORDER BY id",
+            SELECT_REGISTRATIONS
+        ))?;
+        let rows = statement
+            .query_map(
+                params![status_to_str(status), self.registry],
+                RegistrationRow::read,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        rows.into_iter().map(Registration::try_from).collect()
+    }
+}
+
+/// Raw content of a `registrations` row, before any domain validation.
+struct RegistrationRow {
+    id: i64,
+    status: String,
+    identity_provider: Option<String>,
+    identity_id: Option<String>,
+    account_address: String,
+    transaction_hash: Option<String>,
+    error_kind: Option<String>,
+    created_at: i64,
+    updated_at: i64,
+    profile_login: Option<String>,
+    profile_name: Option<String>,
+    profile_avatar_url: Option<String>,
+    profile_created_at: Option<String>,
+}
+
+impl RegistrationRow {
+    fn read(row: &Row) -> rusqlite::Result<Self> {
+        Ok(RegistrationRow {
+            id: row.get(0)?,
+            status: row.get(1)?,
+            identity_provider: row.get(2)?,
+            identity_id: row.get(3)?,
+            account_address: row.get(4)?,
+            transaction_hash: row.get(5)?,
+            error_kind: row.get(6)?,
+            created_at: row.get(7)?,
+            updated_at: row.get(8)?,
+            profile_login: row.get(9)?,
+            profile_name: row.get(10)?,
+            profile_avatar_url: row.get(11)?,
+            profile_created_at: row.get(12)?,
+        })
+    }
+}
+
+impl TryFrom<RegistrationRow> for Registration {
+    type Error = RepositoryError;
+
+    fn try_from(row: RegistrationRow) -> Result<Self, Self::Error> {
+        let identity = match (row.identity_provider, row.identity_id) {
+            (Some(provider), Some(id)) => Some(identity_from_columns(&provider, &id)?),
+            (None, None) => None,
+            _ => {
+                return Err(RepositoryError::Corrupted(format!(
+                    "registration {} has a partial identity",
+                    row.id
+                )))
+            }
+        };
+
+        let profile = row.profile_login.map(|login| IdentityProfile {
+            login,
+            name: row.profile_name,
+            avatar_url: row.profile_avatar_url,
+            created_at: row.profile_created_at,
+            // activity is only used to check eligibility, and is not stored
+            public_repos: None,
+            followers: None,
+            suspended: false,
+        });
+
+        Ok(Registration {
+            id: row.id,
+            status: status_from_str(&row.status)?,
+            identity,
+            profile,
+            account_address: row.account_address,
+            transaction_hash: row.transaction_hash,
+            error_kind: row.error_kind,
+            created_at: from_timestamp(row.created_at),
+            updated_at: from_timestamp(row.updated_at),
+        })
+    }
+}
+
+fn status_to_str(status: RegistrationStatus) -> &'static str {
+    match status {
+        RegistrationStatus::Started => "started",
+        RegistrationStatus::Submitted => "submitted",
+        RegistrationStatus::Failed => "failed",
+        RegistrationStatus::Accepted => "accepted",
+        RegistrationStatus::Rejected => "rejected",
+        RegistrationStatus::AlreadyRegistered => "already_registered",
+    }
+}
+
+fn status_from_str(status: &str) -> Result<RegistrationStatus, RepositoryError> {
+    match status {
+        "started" => Ok(RegistrationStatus::Started),
+        "submitted" => Ok(RegistrationStatus::Submitted),
+        "failed" => Ok(RegistrationStatus::Failed),
+        "accepted" => Ok(RegistrationStatus::Accepted),
+        "rejected" => Ok(RegistrationStatus::Rejected),
+        "already_registered" => Ok(RegistrationStatus::AlreadyRegistered),
+        _ => Err(RepositoryError::Corrupted(format!(
+            "unknown registration status {}",
+            status
+        ))),
+    }
+}
+
+fn identity_to_columns(identity: &Identity) -> (&'static str, String) {
+    (identity.provider(), identity.user_id().to_string())
+}
+
+fn identity_from_columns(provider: &str, id: &str) -> Result<Identity, RepositoryError> {
+    let invalid_id = |_| RepositoryError::Corrupted(format!("invalid {} id {}", provider, id));
+
+    match provider {
+        "github" => id
+            .parse::<u64>()
+            .map(|id| Identity::GitHubId(id.into()))
+            .map_err(invalid_id),
+        "gitlab" => id
+            .parse::<u64>()
+            .map(|id| Identity::GitLabId(id.into()))
+            .map_err(invalid_id),
+        "discord" => id
+            .parse::<u64>()
+            .map(|id| Identity::DiscordId(id.into()))
+            .map_err(invalid_id),
+        _ => Err(RepositoryError::Corrupted(format!(
+            "unknown identity provider {}",
+            provider
+        ))),
+    }
+}
+
+fn to_timestamp(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+fn from_timestamp(timestamp: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(timestamp as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use claim::{assert_none, assert_ok, assert_some_eq};
+    use rocket::tokio;
+
+    use super::SqliteRegistrationRepository;
+    use crate::domain::{
+        entities::RegistrationStatus,
+        services::registration_repository::RegistrationRepository,
+        value_objects::{Identity, IdentityProfile},
+    };
+
+    #[tokio::test]
+    async fn create_registration() {
+        let repository = SqliteRegistrationRepository::open_in_memory().unwrap();
+
+        let registration = repository.create("0x666".to_string()).await.unwrap();
+
+        assert_eq!(registration.status, RegistrationStatus::Started);
+        assert_eq!(registration.account_address, "0x666");
+        assert_none!(registration.identity);
+        assert_none!(registration.profile);
+        assert_none!(registration.transaction_hash);
+    }
+
+    #[tokio::test]
+    async fn save_and_find_submitted_registration() {
+        let repository = SqliteRegistrationRepository::open_in_memory().unwrap();
+
+        let mut registration = repository.create("0x666".to_string()).await.unwrap();
+        registration.identity = Some(Identity::GitHubId(42.into()));
+        registration.submit("0x42".to_string());
+        assert_ok!(repository.save(&registration).await);
+
+        let found = repository
+            .find_by_transaction_hash("0x42")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, registration.id);
+        assert_eq!(found.status, RegistrationStatus::Submitted);
+        assert_some_eq!(found.identity, Identity::GitHubId(42.into()));
+        assert_some_eq!(found.transaction_hash, "0x42".to_string());
+        assert_eq!(found.created_at, registration.created_at);
+
+        let found = repository
+            .find_by_identity(&Identity::GitHubId(42.into()))
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, registration.id);
+    }
+
+    #[tokio::test]
+    async fn save_and_find_identity_profile() {
+        let repository = SqliteRegistrationRepository::open_in_memory().unwrap();
+
+        let profile = IdentityProfile {
+            login: "octocat".to_string(),
+            name: None,
+            avatar_url: Some("https://github.com/images/error/octocat_happy.gif".to_string()),
+            created_at: Some("2008-01-14T04:33:35Z".to_string()),
+            public_repos: None,
+            followers: None,
+            suspended: false,
+        };
+
+        let mut registration = repository.create("0x666".to_string()).await.unwrap();
+        registration.identity = Some(Identity::GitHubId(42.into()));
+        registration.profile = Some(profile.clone());
+        registration.submit("0x42".to_string());
+        repository.save(&registration).await.unwrap();
+
+        let found = repository
+            .find_by_transaction_hash("0x42")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_some_eq!(found.profile, profile);
+    }
+
+    #[tokio::test]
+    async fn find_every_attempt_of_an_identity() {
+        let repository = SqliteRegistrationRepository::open_in_memory().unwrap();
+
+        let mut failed = repository.create("0x666".to_string()).await.unwrap();
+        failed.identity = Some(Identity::GitHubId(42.into()));
+        failed.fail("signature");
+        repository.save(&failed).await.unwrap();
+
+        let mut other = repository.create("0x777".to_string()).await.unwrap();
+        other.identity = Some(Identity::GitHubId(43.into()));
+        other.submit("0x43".to_string());
+        repository.save(&other).await.unwrap();
+
+        let found = repository
+            .find_by_identity(&Identity::GitHubId(42.into()))
+            .await
+            .unwrap();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].status, RegistrationStatus::Failed);
+        assert_some_eq!(found[0].error_kind.clone(), "signature".to_string());
+    }
+
+    #[tokio::test]
+    async fn identities_of_different_providers_are_distinct() {
+        let repository = SqliteRegistrationRepository::open_in_memory().unwrap();
+
+        let mut github = repository.create("0x666".to_string()).await.unwrap();
+        github.identity = Some(Identity::GitHubId(42.into()));
+        repository.save(&github).await.unwrap();
+
+        let mut discord = repository.create("0x777".to_string()).await.unwrap();
+        discord.identity = Some(Identity::DiscordId(42.into()));
+        repository.save(&discord).await.unwrap();
+
+        let found = repository
+            .find_by_identity(&Identity::DiscordId(42.into()))
+            .await
+            .unwrap();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, discord.id);
+        assert_some_eq!(found[0].identity.clone(), Identity::DiscordId(42.into()));
+    }
+
+    #[tokio::test]
+    async fn find_submitted_registrations() {
+        let repository = SqliteRegistrationRepository::open_in_memory().unwrap();
+
+        let mut accepted = repository.create("0x666".to_string()).await.unwrap();
+        accepted.submit("0x42".to_string());
+        accepted.accept();
+        repository.save(&accepted).await.unwrap();
+
+        let mut submitted = repository.create("0x777".to_string()).await.unwrap();
+        submitted.submit("0x43".to_string());
+        repository.save(&submitted).await.unwrap();
+
+        let found = repository
+            .find_by_status(RegistrationStatus::Submitted)
+            .await
+            .unwrap();
+
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, submitted.id);
+    }
+
+    #[tokio::test]
+    async fn registrations_are_scoped_by_registry() {
+        let repository = SqliteRegistrationRepository::open_in_memory().unwrap();
+        let hackathon_repository = repository.for_registry("hackathon");
+
+        let mut default = repository.create("0x666".to_string()).await.unwrap();
+        default.identity = Some(Identity::GitHubId(42.into()));
+        default.submit("0x42".to_string());
+        repository.save(&default).await.unwrap();
+
+        let mut hackathon = hackathon_repository
+            .create("0x666".to_string())
+            .await
+            .unwrap();
+        hackathon.identity = Some(Identity::GitHubId(42.into()));
+        hackathon.submit("0x43".to_string());
+        hackathon_repository.save(&hackathon).await.unwrap();
+
+        let found = hackathon_repository
+            .find_by_identity(&Identity::GitHubId(42.into()))
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, hackathon.id);
+
+        let found = repository
+            .find_by_status(RegistrationStatus::Submitted)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, default.id);
+
+        // transaction hashes are unique across registries
+        let found = repository
+            .find_by_transaction_hash("0x43")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, hackathon.id);
+    }
+
+    #[tokio::test]
+    async fn unknown_transaction_hash() {
+        let repository = SqliteRegistrationRepository::open_in_memory().unwrap();
+
+        let found = repository.find_by_transaction_hash("0x42").await.unwrap();
+
+        assert_none!(found);
+    }
+}
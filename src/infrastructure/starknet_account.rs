@@ -0,0 +1,226 @@
+use starknet::{
+    accounts::{Account, AccountCall, Call, SingleOwnerAccount},
+    core::{crypto::compute_hash_on_elements, types::FieldElement},
+    providers::SequencerGatewayProvider,
+    signers::{LocalWallet, SigningKey},
+};
+
+use super::{
+    json_rpc_provider::{InvokeTransaction, JsonRpcProvider},
+    starknet_provider::StarkNetProviderError,
+};
+
+/// "invoke" short string, prefix of the hash of invoke transactions
+const INVOKE_PREFIX: u64 = 0x696e766f6b65;
+const INVOKE_VERSION: u64 = 1;
+
+/// Account of the service, sending its transactions to the provider the StarkNet state is read from
+pub enum StarkNetAccount {
+    SequencerGateway(SingleOwnerAccount<SequencerGatewayProvider, LocalWallet>),
+    JsonRpc(JsonRpcAccount),
+}
+
+impl StarkNetAccount {
+    pub fn address(&self) -> FieldElement {
+        match self {
+            StarkNetAccount::SequencerGateway(account) => account.address(),
+            StarkNetAccount::JsonRpc(account) => account.address,
+        }
+    }
+
+    /// Sends `calls` in a single transaction with the given `nonce`, and returns its hash.
+    pub async fn execute(
+        &self,
+        calls: &[Call],
+        nonce: FieldElement,
+    ) -> Result<FieldElement, Box<dyn std::error::Error + Send + Sync>> {
+        match self {
+            StarkNetAccount::SequencerGateway(account) => Ok(account
+                .execute(calls)
+                .nonce(nonce)
+                .send()
+                .await?
+                .transaction_hash),
+            StarkNetAccount::JsonRpc(account) => Ok(account.execute(calls, nonce).await?),
+        }
+    }
+}
+
+/// Account whose transactions are signed locally, and sent to a JSON-RPC node
+pub struct JsonRpcAccount {
+    provider: JsonRpcProvider,
+    signing_key: SigningKey,
+    address: FieldElement,
+    chain_id: FieldElement,
+}
+
+impl JsonRpcAccount {
+    pub fn new(
+        provider: JsonRpcProvider,
+        signing_key: SigningKey,
+        address: FieldElement,
+        chain_id: FieldElement,
+    ) -> Self {
+        JsonRpcAccount {
+            provider,
+            signing_key,
+            address,
+            chain_id,
+        }
+    }
+
+    async fn execute(
+        &self,
+        calls: &[Call],
+        nonce: FieldElement,
+    ) -> Result<FieldElement, StarkNetProviderError> {
+        let calldata = execute_calldata(calls);
+
+        // the fee is estimated on the same transaction, signed without fee
+        let estimated_fee = self
+            .provider
+            .estimate_fee(&self.sign(calldata.clone(), FieldElement::ZERO, nonce)?)
+            .await?;
+        // leaves some room for the gas price to rise until the transaction is accepted
+        let max_fee = FieldElement::from(estimated_fee.saturating_add(estimated_fee / 10));
+
+        self.provider
+            .add_invoke_transaction(&self.sign(calldata, max_fee, nonce)?)
+            .await
+    }
+
+    fn sign(
+        &self,
+        calldata: Vec<FieldElement>,
+        max_fee: FieldElement,
+        nonce: FieldElement,
+    ) -> Result<InvokeTransaction, StarkNetProviderError> {
+        let transaction_hash =
+            invoke_transaction_hash(self.address, &calldata, max_fee, self.chain_id, nonce);
+        let signature = self
+            .signing_key
+            .sign(&transaction_hash)
+            .map_err(|e| StarkNetProviderError::Provider(Box::new(e)))?;
+
+        Ok(InvokeTransaction {
+            sender_address: self.address,
+            calldata,
+            max_fee,
+            signature: vec![signature.r, signature.s],
+            nonce,
+        })
+    }
+}
+
+/// Calldata of the `__execute__` entry point of the account for `calls`.
+fn execute_calldata(calls: &[Call]) -> Vec<FieldElement> {
+    let mut call_array = vec![FieldElement::from(calls.len() as u64)];
+    let mut calldata = Vec::new();
+    for call in calls {
+        call_array.extend([
+            call.to,
+            call.selector,
+            FieldElement::from(calldata.len() as u64),
+            FieldElement::from(call.calldata.len() as u64),
+        ]);
+        calldata.extend_from_slice(&call.calldata);
+    }
+
+    call_array.push(FieldElement::from(calldata.len() as u64));
+    call_array.extend(calldata);
+    call_array
+}
+
+/// Hash of an invoke transaction of version 1, signed by the account.
+fn invoke_transaction_hash(
+    sender_address: FieldElement,
+    calldata: &[FieldElement],
+    max_fee: FieldElement,
+    chain_id: FieldElement,
+    nonce: FieldElement,
+) -> FieldElement {
+    compute_hash_on_elements(&[
+        FieldElement::from(INVOKE_PREFIX),
+        FieldElement::from(INVOKE_VERSION),
+        sender_address,
+        FieldElement::ZERO,
+        compute_hash_on_elements(calldata),
+        max_fee,
+        chain_id,
+        nonce,
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::{accounts::Call, core::types::FieldElement};
+
+    use super::{execute_calldata, invoke_transaction_hash};
+
+    #[test]
+    fn calls_are_encoded_as_a_call_array() {
+        let calls = [
+            Call {
+                to: FieldElement::from(0x10u64),
+                selector: FieldElement::from(0x20u64),
+                calldata: vec![FieldElement::from(1u64), FieldElement::from(2u64)],
+            },
+            Call {
+                to: FieldElement::from(0x11u64),
+                selector: FieldElement::from(0x21u64),
+                calldata: vec![FieldElement::from(3u64)],
+            },
+        ];
+
+        assert_eq!(
+            execute_calldata(&calls),
+            [2u64, 0x10, 0x20, 0, 2, 0x11, 0x21, 2, 1, 3, 1, 2, 3]
+                .into_iter()
+                .map(FieldElement::from)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn transaction_hash_is_bound_to_fee_chain_and_nonce() {
+        let calldata = [FieldElement::ONE];
+        let hash = invoke_transaction_hash(
+            FieldElement::from(0x42u64),
+            &calldata,
+            FieldElement::ZERO,
+            FieldElement::ONE,
+            FieldElement::from(7u64),
+        );
+
+        assert_ne!(
+            hash,
+            invoke_transaction_hash(
+                FieldElement::from(0x42u64),
+                &calldata,
+                FieldElement::from(100u64),
+                FieldElement::ONE,
+                FieldElement::from(7u64),
+            )
+        );
+        assert_ne!(
+            hash,
+            invoke_transaction_hash(
+                FieldElement::from(0x42u64),
+                &calldata,
+                FieldElement::ZERO,
+                FieldElement::from(2u64),
+                FieldElement::from(7u64),
+            )
+        );
+        assert_ne!(
+            hash,
+            invoke_transaction_hash(
+                FieldElement::from(0x42u64),
+                &calldata,
+                FieldElement::ZERO,
+                FieldElement::ONE,
+                FieldElement::from(8u64),
+            )
+        );
+    }
+}
@@ -1,20 +1,36 @@
-use std::str::FromStr;
+use std::{str::FromStr, time::Duration};
 
+use reqwest::Url;
 use starknet::{
     accounts::{single_owner::GetNonceError, SingleOwnerAccount},
     core::{
         chain_id::{MAINNET, TESTNET},
-        types::{BlockId, FieldElement, InvokeFunctionTransactionRequest},
+        types::FieldElement,
         utils::get_selector_from_name,
     },
-    providers::{Provider, SequencerGatewayProvider},
+    providers::SequencerGatewayProvider,
     signers::{LocalWallet, SigningKey},
 };
+use thiserror::Error;
+
+use super::{
+    json_rpc_provider::JsonRpcProvider,
+    public_key_cache::PublicKeyCache,
+    starknet_account::{JsonRpcAccount, StarkNetAccount},
+    starknet_provider::{StarkNetProvider, StarkNetProviderError},
+};
 
 pub struct StarkNetClient {
-    pub provider: SequencerGatewayProvider,
-    pub account: SingleOwnerAccount<SequencerGatewayProvider, LocalWallet>,
+    /// Reads the StarkNet state, transactions are sent by `account` to the same provider
+    pub provider: StarkNetProvider,
+    pub account: StarkNetAccount,
     pub badge_registry_address: FieldElement,
+    pub chain_id: FieldElement,
+    /// Class hashes of the account implementations allowed to sign, any class is allowed if empty
+    pub account_class_hashes: Vec<FieldElement>,
+    pub signature_verification: SignatureVerification,
+    /// Public keys of the accounts whose signatures were verified off-chain, by account address
+    pub public_keys: PublicKeyCache,
 }
 
 impl StarkNetClient {
@@ -23,76 +39,246 @@ impl StarkNetClient {
         hex_private_key: &str,
         hex_badge_registry_address: &str,
         chain: StarkNetChain,
+        gateway: SequencerGateway,
     ) -> Self {
-        let provider = match chain {
-            StarkNetChain::Testnet => SequencerGatewayProvider::starknet_alpha_goerli(),
-            StarkNetChain::Mainnet => SequencerGatewayProvider::starknet_alpha_mainnet(),
-        };
-        let account_provider = match chain {
-            StarkNetChain::Testnet => SequencerGatewayProvider::starknet_alpha_goerli(),
-            StarkNetChain::Mainnet => SequencerGatewayProvider::starknet_alpha_mainnet(),
-        };
-        let chain_id = match chain {
-            StarkNetChain::Testnet => TESTNET,
-            StarkNetChain::Mainnet => MAINNET,
-        };
-        let signer = LocalWallet::from(SigningKey::from_secret_scalar(
-            FieldElement::from_hex_be(hex_private_key).expect("Invalid private key"),
-        ));
+        let provider = SequencerGatewayProvider::new(
+            gateway.gateway_url.clone(),
+            gateway.feeder_gateway_url.clone(),
+        );
+        let account_provider =
+            SequencerGatewayProvider::new(gateway.gateway_url, gateway.feeder_gateway_url);
+        let chain_id = chain.chain_id();
+        let signer = LocalWallet::from(signing_key(hex_private_key));
+        let account_address =
+            FieldElement::from_hex_be(hex_account_address).expect("Invalid account address");
+
+        StarkNetClient::with_provider(
+            StarkNetProvider::SequencerGateway(provider),
+            StarkNetAccount::SequencerGateway(SingleOwnerAccount::new(
+                account_provider,
+                signer,
+                account_address,
+                chain_id,
+            )),
+            hex_badge_registry_address,
+            chain_id,
+        )
+    }
+
+    /// Reads the StarkNet state from, and sends transactions to, the JSON-RPC node at `url`.
+    pub fn new_json_rpc(
+        hex_account_address: &str,
+        hex_private_key: &str,
+        hex_badge_registry_address: &str,
+        chain: StarkNetChain,
+        url: String,
+    ) -> Self {
+        let chain_id = chain.chain_id();
         let account_address =
             FieldElement::from_hex_be(hex_account_address).expect("Invalid account address");
+
+        StarkNetClient::with_provider(
+            StarkNetProvider::JsonRpc(JsonRpcProvider::new(url.clone())),
+            StarkNetAccount::JsonRpc(JsonRpcAccount::new(
+                JsonRpcProvider::new(url),
+                signing_key(hex_private_key),
+                account_address,
+                chain_id,
+            )),
+            hex_badge_registry_address,
+            chain_id,
+        )
+    }
+
+    fn with_provider(
+        provider: StarkNetProvider,
+        account: StarkNetAccount,
+        hex_badge_registry_address: &str,
+        chain_id: FieldElement,
+    ) -> Self {
         let badge_registry_address = FieldElement::from_hex_be(hex_badge_registry_address)
             .expect("Invalid address for badge_registry");
 
         StarkNetClient {
             provider,
-            account: SingleOwnerAccount::new(account_provider, signer, account_address, chain_id),
+            account,
             badge_registry_address,
+            chain_id,
+            account_class_hashes: Vec::new(),
+            signature_verification: SignatureVerification::OnChain,
+            public_keys: PublicKeyCache::default(),
         }
     }
 
+    /// Only accepts signatures of accounts whose class is one of `hex_class_hashes`.
+    pub fn with_account_class_hashes(mut self, hex_class_hashes: &[String]) -> Self {
+        self.account_class_hashes = hex_class_hashes
+            .iter()
+            .map(|hex_class_hash| {
+                FieldElement::from_hex_be(hex_class_hash).expect("Invalid account class hash")
+            })
+            .collect();
+        self
+    }
+
+    pub fn with_signature_verification(
+        mut self,
+        signature_verification: SignatureVerification,
+    ) -> Self {
+        self.signature_verification = signature_verification;
+        self
+    }
+
+    /// Caches up to `capacity` public keys of accounts verifying signatures off-chain, for `ttl`.
+    pub fn with_public_key_cache(mut self, capacity: usize, ttl: Duration) -> Self {
+        self.public_keys = PublicKeyCache::new(capacity, ttl);
+        self
+    }
+
     pub async fn get_2d_nonce(
         &self,
         nonce_key: FieldElement,
-    ) -> Result<FieldElement, GetNonceError<<SequencerGatewayProvider as Provider>::Error>> {
+    ) -> Result<FieldElement, GetNonceError<StarkNetProviderError>> {
         let call_result = self
             .provider
             .call_contract(
-                InvokeFunctionTransactionRequest {
-                    contract_address: self.account.address(),
-                    entry_point_selector: get_selector_from_name("get_nonce").unwrap(),
-                    calldata: vec![nonce_key],
-                    signature: vec![],
-                    max_fee: FieldElement::ZERO,
-                },
-                BlockId::Latest,
+                self.account.address(),
+                get_selector_from_name("get_nonce").unwrap(),
+                vec![nonce_key],
             )
             .await
             .map_err(GetNonceError::ProviderError)?;
 
-        if call_result.result.len() == 1 {
-            Ok(call_result.result[0])
+        if call_result.len() == 1 {
+            Ok(call_result[0])
         } else {
             Err(GetNonceError::InvalidResponseLength {
                 expected: 1,
-                actual: call_result.result.len(),
+                actual: call_result.len(),
             })
         }
     }
 }
 
+fn signing_key(hex_private_key: &str) -> SigningKey {
+    SigningKey::from_secret_scalar(
+        FieldElement::from_hex_be(hex_private_key).expect("Invalid private key"),
+    )
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum StarkNetChain {
     Testnet,
+    Testnet2,
     Mainnet,
+    /// Any other chain, e.g. a devnet, identified by its chain id
+    Custom(FieldElement),
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum StarkNetChainError {
+    #[error("Chain id {0} is not a valid hexadecimal field element")]
+    InvalidChainId(String),
+    #[error(
+        "Unknown StarkNet chain {0}, expected MAINNET, TESTNET, TESTNET2, \
+        a chain id short string such as SN_SEPOLIA or a hexadecimal chain id"
+    )]
+    UnknownChain(String),
+}
+
+/// Encodes a chain id short string, made of at most 31 upper case letters, digits or underscores.
+fn short_string(name: &str) -> Option<FieldElement> {
+    if name.is_empty()
+        || name.len() > 31
+        || !name
+            .bytes()
+            .all(|byte| byte.is_ascii_uppercase() || byte.is_ascii_digit() || byte == b'_')
+    {
+        return None;
+    }
+
+    let mut bytes = [0u8; 32];
+    bytes[32 - name.len()..].copy_from_slice(name.as_bytes());
+    // 31 bytes always fit in a field element
+    FieldElement::from_bytes_be(&bytes).ok()
+}
+
+impl StarkNetChain {
+    pub fn chain_id(&self) -> FieldElement {
+        match self {
+            StarkNetChain::Testnet => TESTNET,
+            StarkNetChain::Testnet2 => short_string("SN_GOERLI2").unwrap(),
+            StarkNetChain::Mainnet => MAINNET,
+            StarkNetChain::Custom(chain_id) => *chain_id,
+        }
+    }
+
+    /// Public sequencer gateway of the chain, custom chains have none
+    pub fn default_gateway(&self) -> Option<SequencerGateway> {
+        let base_url = match self {
+            StarkNetChain::Testnet => "https://alpha4.starknet.io",
+            StarkNetChain::Testnet2 => "https://alpha4-2.starknet.io",
+            StarkNetChain::Mainnet => "https://alpha-mainnet.starknet.io",
+            StarkNetChain::Custom(_) => return None,
+        };
+
+        Some(SequencerGateway {
+            gateway_url: Url::parse(&format!("{}/gateway", base_url)).unwrap(),
+            feeder_gateway_url: Url::parse(&format!("{}/feeder_gateway", base_url)).unwrap(),
+        })
+    }
 }
 
 impl FromStr for StarkNetChain {
-    type Err = ();
+    type Err = StarkNetChainError;
 
     fn from_str(input: &str) -> Result<StarkNetChain, Self::Err> {
+        let chain_id = match input {
+            "TESTNET" => return Ok(StarkNetChain::Testnet),
+            "TESTNET2" => return Ok(StarkNetChain::Testnet2),
+            "MAINNET" => return Ok(StarkNetChain::Mainnet),
+            _ if input.starts_with("0x") => FieldElement::from_hex_be(input)
+                .map_err(|_| StarkNetChainError::InvalidChainId(input.to_string()))?,
+            _ => short_string(input)
+                .ok_or_else(|| StarkNetChainError::UnknownChain(input.to_string()))?,
+        };
+
+        // chain ids of known chains get their public gateway
+        Ok([
+            StarkNetChain::Testnet,
+            StarkNetChain::Testnet2,
+            StarkNetChain::Mainnet,
+        ]
+        .into_iter()
+        .find(|chain| chain.chain_id() == chain_id)
+        .unwrap_or(StarkNetChain::Custom(chain_id)))
+    }
+}
+
+/// Sequencer gateway of a chain, through which transactions are sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SequencerGateway {
+    pub gateway_url: Url,
+    pub feeder_gateway_url: Url,
+}
+
+/// How the signatures of accounts are verified
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureVerification {
+    /// Every signature is checked by calling `is_valid_signature` on the account
+    OnChain,
+    /// `(r, s)` signatures are checked locally against the cached public key of the account,
+    /// other signatures and accounts without `get_public_key` are checked on-chain
+    OffChain,
+}
+
+impl FromStr for SignatureVerification {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<SignatureVerification, Self::Err> {
         match input {
-            "TESTNET" => Ok(StarkNetChain::Testnet),
-            "MAINNET" => Ok(StarkNetChain::Mainnet),
+            "ON_CHAIN" => Ok(SignatureVerification::OnChain),
+            "OFF_CHAIN" => Ok(SignatureVerification::OffChain),
             _ => Err(()),
         }
     }
@@ -100,13 +286,56 @@ impl FromStr for StarkNetChain {
 
 #[cfg(test)]
 mod tests {
-    use starknet::core::types::FieldElement;
-    use std::{thread, time::Duration};
+    use rocket::tokio::time::sleep;
+    use starknet::core::{
+        chain_id::{MAINNET, TESTNET},
+        types::FieldElement,
+    };
+    use std::time::Duration;
+
+    use super::{StarkNetChain, StarkNetChainError};
+    use crate::domain::{
+        services::onchain_registry::OnChainRegistry, value_objects::TransactionStatus,
+    };
+
+    #[test]
+    fn known_chains_are_parsed_by_name_or_chain_id() {
+        assert_eq!("MAINNET".parse(), Ok(StarkNetChain::Mainnet));
+        assert_eq!("SN_MAIN".parse(), Ok(StarkNetChain::Mainnet));
+        assert_eq!("TESTNET".parse(), Ok(StarkNetChain::Testnet));
+        assert_eq!(
+            format!("{:#x}", TESTNET).parse(),
+            Ok(StarkNetChain::Testnet)
+        );
+        assert_eq!("SN_GOERLI2".parse(), Ok(StarkNetChain::Testnet2));
+        assert_eq!(StarkNetChain::Mainnet.chain_id(), MAINNET);
+    }
 
-    use starknet::{core::types::TransactionStatus, providers::Provider};
+    #[test]
+    fn custom_chains_are_parsed_by_chain_id() {
+        let sepolia = FieldElement::from_hex_be("0x534e5f5345504f4c4941").unwrap();
+
+        assert_eq!("SN_SEPOLIA".parse(), Ok(StarkNetChain::Custom(sepolia)));
+        assert_eq!(
+            "0x534e5f5345504f4c4941".parse(),
+            Ok(StarkNetChain::Custom(sepolia))
+        );
+        assert_eq!(StarkNetChain::Custom(sepolia).default_gateway(), None);
+    }
+
+    #[test]
+    fn invalid_chains_are_rejected() {
+        assert_eq!(
+            "mainnet".parse::<StarkNetChain>(),
+            Err(StarkNetChainError::UnknownChain("mainnet".to_string()))
+        );
+        assert_eq!(
+            "0xnot-hex".parse::<StarkNetChain>(),
+            Err(StarkNetChainError::InvalidChainId("0xnot-hex".to_string()))
+        );
+    }
 
     impl super::StarkNetClient {
-        #[cfg(test)]
         pub async fn wait_for_transaction_acceptance(
             &self,
             transaction_hash: FieldElement,
@@ -117,26 +346,24 @@ mod tests {
             );
 
             loop {
-                let receipt = match self.provider.get_transaction_status(transaction_hash).await {
-                    Ok(receipt) => receipt,
+                let status = match self.get_transaction_status(transaction_hash).await {
+                    Ok(status) => status,
                     Err(e) => {
                         warn!("{}", e);
-                        thread::sleep(Duration::from_secs(3));
+                        sleep(Duration::from_secs(3)).await;
                         continue;
                     }
                 };
 
-                println!("Transaction is {:?}", receipt.status);
+                println!("Transaction is {:?}", status);
 
-                break match receipt.status {
-                    TransactionStatus::NotReceived
-                    | TransactionStatus::Received
-                    | TransactionStatus::Pending => {
-                        thread::sleep(Duration::from_secs(3));
+                break match status {
+                    TransactionStatus::NotReceived | TransactionStatus::Pending => {
+                        sleep(Duration::from_secs(3)).await;
                         continue;
                     }
                     TransactionStatus::AcceptedOnL2 | TransactionStatus::AcceptedOnL1 => Ok(()),
-                    TransactionStatus::Rejected => Err(()),
+                    TransactionStatus::Rejected { .. } => Err(()),
                 };
             }
         }
@@ -0,0 +1,113 @@
+use starknet::{
+    core::types::{
+        BlockId, FieldElement, InvokeFunctionTransactionRequest, StarknetError, StarknetErrorCode,
+        TransactionStatus as StarkNetTransactionStatus,
+    },
+    providers::{sequencer::ProviderError, Provider, SequencerGatewayProvider},
+};
+use thiserror::Error;
+
+use crate::domain::value_objects::TransactionStatus;
+
+use super::json_rpc_provider::JsonRpcProvider;
+
+/// Source of the StarkNet state read by the service
+pub enum StarkNetProvider {
+    SequencerGateway(SequencerGatewayProvider),
+    JsonRpc(JsonRpcProvider),
+}
+
+#[derive(Debug, Error)]
+pub enum StarkNetProviderError {
+    #[error("Contract is not deployed")]
+    ContractNotFound,
+    #[error("Contract call failed: {0}")]
+    Contract(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("StarkNet provider request failed")]
+    Provider(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl From<ProviderError> for StarkNetProviderError {
+    fn from(error: ProviderError) -> Self {
+        match error {
+            ProviderError::StarknetError(StarknetError {
+                code: StarknetErrorCode::UninitializedContract,
+                ..
+            }) => StarkNetProviderError::ContractNotFound,
+            ProviderError::StarknetError(e) => StarkNetProviderError::Contract(Box::new(e)),
+            e => StarkNetProviderError::Provider(Box::new(e)),
+        }
+    }
+}
+
+impl StarkNetProvider {
+    pub async fn call_contract(
+        &self,
+        contract_address: FieldElement,
+        entry_point_selector: FieldElement,
+        calldata: Vec<FieldElement>,
+    ) -> Result<Vec<FieldElement>, StarkNetProviderError> {
+        match self {
+            StarkNetProvider::SequencerGateway(provider) => Ok(provider
+                .call_contract(
+                    InvokeFunctionTransactionRequest {
+                        contract_address,
+                        entry_point_selector,
+                        calldata,
+                        signature: vec![],
+                        max_fee: FieldElement::ZERO,
+                    },
+                    BlockId::Latest,
+                )
+                .await?
+                .result),
+            StarkNetProvider::JsonRpc(provider) => {
+                provider
+                    .call(contract_address, entry_point_selector, calldata)
+                    .await
+            }
+        }
+    }
+
+    pub async fn get_class_hash_at(
+        &self,
+        contract_address: FieldElement,
+    ) -> Result<FieldElement, StarkNetProviderError> {
+        match self {
+            StarkNetProvider::SequencerGateway(provider) => Ok(provider
+                .get_class_hash_at(contract_address, BlockId::Latest)
+                .await?),
+            StarkNetProvider::JsonRpc(provider) => {
+                provider.get_class_hash_at(contract_address).await
+            }
+        }
+    }
+
+    pub async fn get_transaction_status(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> Result<TransactionStatus, StarkNetProviderError> {
+        let provider = match self {
+            StarkNetProvider::SequencerGateway(provider) => provider,
+            StarkNetProvider::JsonRpc(provider) => {
+                return provider.get_transaction_status(transaction_hash).await
+            }
+        };
+
+        let status_info = provider.get_transaction_status(transaction_hash).await?;
+
+        Ok(match status_info.status {
+            StarkNetTransactionStatus::NotReceived => TransactionStatus::NotReceived,
+            StarkNetTransactionStatus::Received | StarkNetTransactionStatus::Pending => {
+                TransactionStatus::Pending
+            }
+            StarkNetTransactionStatus::AcceptedOnL2 => TransactionStatus::AcceptedOnL2,
+            StarkNetTransactionStatus::AcceptedOnL1 => TransactionStatus::AcceptedOnL1,
+            StarkNetTransactionStatus::Rejected => TransactionStatus::Rejected {
+                reason: status_info
+                    .transaction_failure_reason
+                    .and_then(|failure| failure.error_message),
+            },
+        })
+    }
+}
@@ -1,8 +1,30 @@
+use std::sync::Arc;
+
 use dotenv::dotenv;
+use rocket::fairing::AdHoc;
 
 use crate::{
-    application::registerer::{Registerer, RegistererImpl},
-    infrastructure::{github_client::GitHubClient, starknet_client::StarkNetClient},
+    application::{
+        confirmation_tracker::{self, ConfirmationTracker},
+        registerer::{Registerer, RegistererImpl},
+    },
+    domain::services::{
+        device_authorization_provider::DeviceAuthorizationProvider, nonce_store::NonceStore,
+        registration_repository::RegistrationRepository,
+    },
+    infrastructure::{
+        discord_client::DiscordClient,
+        github_client::GitHubClient,
+        gitlab_client::GitLabClient,
+        in_memory_authorization_state_store::InMemoryAuthorizationStateStore,
+        in_memory_idempotency_store::InMemoryIdempotencyStore,
+        in_memory_identity_session_store::InMemoryIdentitySessionStore,
+        in_memory_nonce_store::InMemoryNonceStore,
+        rule_based_eligibility_policy::{EligibilityRules, RuleBasedEligibilityPolicy},
+        sqlite_registration_repository::SqliteRegistrationRepository,
+        starknet_client::StarkNetClient,
+    },
+    rest::registrations::BadgeRegistries,
 };
 
 #[macro_use]
@@ -15,7 +37,7 @@ mod infrastructure;
 mod rest;
 
 #[launch]
-fn rocket() -> _ {
+async fn rocket() -> _ {
     info!("loading configuration...");
     dotenv().ok();
     let conf = config::load();
@@ -26,14 +48,176 @@ fn rocket() -> _ {
         conf.github_secret,
         conf.access_token_url,
         conf.user_api_url,
-    );
-    let starknet_client = StarkNetClient::new(
-        &conf.hex_account_address,
-        &conf.hex_private_key,
+        conf.applications_api_url,
+        conf.device_code_url,
+    )
+    .with_authorization_page(conf.authorize_url, conf.redirect_uri, conf.oauth_scopes);
+    let gitlab_client = conf.gitlab.map(|gitlab| {
+        GitLabClient::new(
+            gitlab.client_id,
+            gitlab.client_secret,
+            gitlab.redirect_uri,
+            gitlab.access_token_url,
+            gitlab.user_api_url,
+        )
+    });
+    let discord_client = conf.discord.map(|discord| {
+        DiscordClient::new(
+            discord.client_id,
+            discord.client_secret,
+            discord.redirect_uri,
+            discord.access_token_url,
+            discord.user_api_url,
+        )
+    });
+    let device_authorization_provider =
+        Box::new(github_client.clone()) as Box<dyn DeviceAuthorizationProvider>;
+
+    // challenges and identity sessions are shared by all badge registries, which record their
+    // registrations in the same database
+    let nonce_store: Arc<dyn NonceStore> = Arc::new(InMemoryNonceStore::new(
+        conf.challenge_capacity,
+        conf.challenge_ttl,
+    ));
+    let repository = SqliteRegistrationRepository::open(&conf.database_path)
+        .expect("Failed to open the registrations database");
+    let authorization_states = Arc::new(InMemoryAuthorizationStateStore::new(
+        conf.oauth_state_capacity,
+        conf.oauth_state_ttl,
+    ));
+    let identity_sessions = Arc::new(InMemoryIdentitySessionStore::new(conf.identity_session_ttl));
+
+    // each badge registry has its own StarkNet client, and its own tracker to resend its transactions
+    let mut trackers = Vec::new();
+    let mut registerers = |hex_badge_registry_address: &str,
+                           repository: Arc<dyn RegistrationRepository>| {
+        let starknet_client = match &conf.rpc_url {
+            Some(rpc_url) => StarkNetClient::new_json_rpc(
+                &conf.hex_account_address,
+                &conf.hex_private_key,
+                hex_badge_registry_address,
+                conf.chain.clone(),
+                rpc_url.clone(),
+            ),
+            None => StarkNetClient::new(
+                &conf.hex_account_address,
+                &conf.hex_private_key,
+                hex_badge_registry_address,
+                conf.chain.clone(),
+                conf.gateway
+                    .clone()
+                    .expect("No sequencer gateway configured"),
+            ),
+        }
+        .with_account_class_hashes(&conf.account_class_hashes)
+        .with_signature_verification(conf.signature_verification)
+        .with_public_key_cache(conf.public_key_cache_capacity, conf.public_key_cache_ttl);
+        let starknet_client = Arc::new(starknet_client);
+
+        let (tracker_handle, tracker_receiver) = confirmation_tracker::channel();
+        trackers.push(
+            ConfirmationTracker::new(
+                starknet_client.clone(),
+                repository.clone(),
+                tracker_receiver,
+                conf.max_registration_attempts,
+                conf.confirmation_poll_interval,
+            )
+            .with_dropped_after_polls(conf.dropped_transaction_polls)
+            .with_max_watch_time(conf.max_confirmation_watch_time),
+        );
+
+        let gitlab_registerer = gitlab_client.clone().map(|gitlab_client| {
+            Box::new(RegistererImpl::new(
+                gitlab_client,
+                starknet_client.clone(),
+                nonce_store.clone(),
+                repository.clone(),
+                tracker_handle.clone(),
+                Box::new(RuleBasedEligibilityPolicy::new(EligibilityRules::default())),
+            )) as Box<dyn Registerer<GitLabClient, StarkNetClient>>
+        });
+        let discord_registerer = discord_client.clone().map(|discord_client| {
+            Box::new(RegistererImpl::new(
+                discord_client,
+                starknet_client.clone(),
+                nonce_store.clone(),
+                repository.clone(),
+                tracker_handle.clone(),
+                Box::new(RuleBasedEligibilityPolicy::new(EligibilityRules::default())),
+            )) as Box<dyn Registerer<DiscordClient, StarkNetClient>>
+        });
+
+        let registerer = RegistererImpl::new(
+            github_client.clone(),
+            starknet_client,
+            nonce_store.clone(),
+            repository.clone(),
+            tracker_handle,
+            Box::new(RuleBasedEligibilityPolicy::new(
+                conf.github_eligibility.clone(),
+            )),
+        )
+        .with_required_memberships(conf.github_required_memberships.clone())
+        .with_authorization_states(authorization_states.clone())
+        .with_identity_sessions(identity_sessions.clone());
+        let registerer = if conf.codes_without_oauth_state_allowed {
+            registerer.with_codes_without_state()
+        } else {
+            registerer
+        };
+
+        (
+            Box::new(registerer) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            gitlab_registerer,
+            discord_registerer,
+        )
+    };
+
+    let (registerer, gitlab_registerer, discord_registerer) = registerers(
         &conf.hex_badge_registry_address,
-        conf.chain,
+        Arc::new(repository.clone()),
     );
-    let registerer = RegistererImpl::new(github_client, starknet_client);
+    let mut badge_registries = BadgeRegistries::default();
+    for (name, hex_badge_registry_address) in &conf.hex_badge_registry_addresses {
+        let (registerer, gitlab_registerer, discord_registerer) = registerers(
+            hex_badge_registry_address,
+            Arc::new(repository.for_registry(name)),
+        );
+        badge_registries.github.insert(name.clone(), registerer);
+        if let Some(gitlab_registerer) = gitlab_registerer {
+            badge_registries
+                .gitlab
+                .insert(name.clone(), gitlab_registerer);
+        }
+        if let Some(discord_registerer) = discord_registerer {
+            badge_registries
+                .discord
+                .insert(name.clone(), discord_registerer);
+        }
+    }
+
+    // transactions submitted before a restart are watched again
+    for tracker in trackers.iter_mut() {
+        tracker.resume().await;
+    }
 
-    rest::router::new(Box::new(registerer) as Box<dyn Registerer<GitHubClient, StarkNetClient>>)
+    rest::router::new_with_registries(
+        registerer,
+        gitlab_registerer,
+        discord_registerer,
+        Some(device_authorization_provider),
+        Box::new(
+            InMemoryIdempotencyStore::new(conf.idempotency_key_ttl)
+                .with_lease(conf.idempotency_lease),
+        ),
+        badge_registries,
+    )
+    .attach(AdHoc::on_liftoff("Confirmation trackers", |_| {
+        Box::pin(async move {
+            for tracker in trackers {
+                rocket::tokio::spawn(tracker.run());
+            }
+        })
+    }))
 }
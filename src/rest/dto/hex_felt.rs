@@ -1,7 +1,10 @@
 use std::fmt::Display;
 
 use okapi::openapi3::SchemaObject;
-use rocket::serde::{de, Deserialize, Serialize, Serializer};
+use rocket::{
+    request::FromParam,
+    serde::{de, Deserialize, Serialize, Serializer},
+};
 use schemars::schema::InstanceType;
 use starknet::core::types::FieldElement;
 
@@ -46,6 +49,16 @@ impl<'de> Deserialize<'de> for HexFieldElement {
     }
 }
 
+impl<'a> FromParam<'a> for HexFieldElement {
+    type Error = &'a str;
+
+    fn from_param(param: &'a str) -> Result<Self, Self::Error> {
+        FieldElement::from_hex_be(param)
+            .map(HexFieldElement)
+            .map_err(|_| param)
+    }
+}
+
 impl From<FieldElement> for HexFieldElement {
     fn from(value: FieldElement) -> Self {
         HexFieldElement(value)
@@ -0,0 +1,62 @@
+use okapi::openapi3::{Object, Parameter, ParameterValue};
+use rocket::{
+    request::{FromRequest, Outcome},
+    Request,
+};
+use rocket_okapi::{
+    gen::OpenApiGenerator,
+    request::{OpenApiFromRequest, RequestHeaderInput},
+};
+
+const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Value of the `Idempotency-Key` request header
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IdempotencyKey(String);
+
+impl IdempotencyKey {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IdempotencyKey {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        match request.headers().get_one(IDEMPOTENCY_KEY_HEADER) {
+            Some(key) if !key.is_empty() => Outcome::Success(IdempotencyKey(key.to_string())),
+            _ => Outcome::Forward(()),
+        }
+    }
+}
+
+impl<'r> OpenApiFromRequest<'r> for IdempotencyKey {
+    fn from_request_input(
+        gen: &mut OpenApiGenerator,
+        _name: String,
+        required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        Ok(RequestHeaderInput::Parameter(Parameter {
+            name: IDEMPOTENCY_KEY_HEADER.to_string(),
+            location: "header".to_string(),
+            description: Some(
+                "Unique key of the request. Retries sent with the same key get the same response."
+                    .to_string(),
+            ),
+            required,
+            deprecated: false,
+            allow_empty_value: false,
+            value: ParameterValue::Schema {
+                style: None,
+                explode: None,
+                allow_reserved: false,
+                schema: gen.json_schema::<String>(),
+                example: None,
+                examples: None,
+            },
+            extensions: Object::default(),
+        }))
+    }
+}
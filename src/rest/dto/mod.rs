@@ -1,30 +1,48 @@
+use rocket::serde::json::serde_json;
 use rocket::serde::Deserialize;
 use rocket::serde::Serialize;
 use schemars::JsonSchema;
+use sha2::{Digest, Sha256};
 
-use self::hex_felt::HexFieldElement;
+pub use self::hex_felt::HexFieldElement;
+pub use self::idempotency_key::IdempotencyKey;
+use crate::domain::value_objects::{
+    DeviceAuthorization, DeviceAuthorizationStatus, IdentityProfile, TokenGrant, TransactionStatus,
+};
 use crate::infrastructure::StarknetSignature;
 use crate::infrastructure::StarknetSignedData;
 
 mod hex_felt;
+mod idempotency_key;
 
-#[derive(Deserialize, Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash, JsonSchema)]
-#[serde(crate = "rocket::serde")]
-pub struct Signature {
-    pub r: HexFieldElement,
-    pub s: HexFieldElement,
+/// Signature of the account, either `{"r", "s"}` for a Stark ECDSA signature,
+/// or the whole array checked by the account, e.g. for multisig or guardian based accounts.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Eq, PartialEq, PartialOrd, Ord, Hash, JsonSchema,
+)]
+#[serde(crate = "rocket::serde", untagged)]
+pub enum Signature {
+    Ecdsa {
+        r: HexFieldElement,
+        s: HexFieldElement,
+    },
+    Array(Vec<HexFieldElement>),
 }
 
 impl From<Signature> for StarknetSignature {
     fn from(val: Signature) -> Self {
-        StarknetSignature {
-            r: val.r.into(),
-            s: val.s.into(),
+        match val {
+            Signature::Ecdsa { r, s } => StarknetSignature(vec![r.into(), s.into()]),
+            Signature::Array(signature) => {
+                StarknetSignature(signature.into_iter().map(Into::into).collect())
+            }
         }
     }
 }
 
-#[derive(Deserialize, Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash, JsonSchema)]
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Eq, PartialEq, PartialOrd, Ord, Hash, JsonSchema,
+)]
 #[serde(crate = "rocket::serde")]
 pub struct SignedData {
     pub hash: HexFieldElement,
@@ -40,16 +58,273 @@ impl From<SignedData> for StarknetSignedData {
     }
 }
 
+#[derive(Serialize, Deserialize, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct UserRegistrationRequest<'r> {
+    /// OAuth authorization code, required unless `access_token` or `session` is given
+    pub authorization_code: Option<&'r str>,
+    /// User access token, e.g. obtained through the device authorization flow
+    pub access_token: Option<&'r str>,
+    /// Identity session opened by `GET /oauth/github/callback`
+    pub session: Option<&'r str>,
+    /// OAuth state of the authorization request issued by `GET /oauth/github/authorize`,
+    /// sent back along with the authorization code
+    pub state: Option<&'r str>,
+    pub account_address: HexFieldElement,
+    pub signed_data: SignedData,
+    /// Nonce of the registration challenge, as returned by `GET /registrations/challenge`
+    /// for `account_address`
+    pub nonce: &'r str,
+    /// Name of the badge registry to register with, the default registry if not given
+    pub registry: Option<&'r str>,
+}
+
+impl UserRegistrationRequest<'_> {
+    /// Digest of the request, telling apart different requests sent with the same idempotency key.
+    pub fn fingerprint(&self) -> String {
+        let body = serde_json::to_vec(self).expect("registration requests are serializable");
+        base64::encode(Sha256::digest(body))
+    }
+}
+
+#[derive(Serialize, JsonSchema, Debug, Clone)]
+#[serde(crate = "rocket::serde")]
+pub struct UserRegistrationResponse {
+    /// Hash of the registration transaction, unknown if the identity was registered by another mean
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_hash: Option<HexFieldElement>,
+    /// Set when the identity was already registered with this account, and no transaction was sent
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub already_registered: bool,
+    pub profile: UserProfile,
+}
+
+/// Public profile of the user, as given by the identity provider
+#[derive(Serialize, JsonSchema, Debug, Clone)]
+#[serde(crate = "rocket::serde")]
+pub struct UserProfile {
+    pub login: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar_url: Option<String>,
+    /// Creation date of the account, in RFC 3339 format
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+}
+
+impl From<IdentityProfile> for UserProfile {
+    fn from(profile: IdentityProfile) -> Self {
+        UserProfile {
+            login: profile.login,
+            name: profile.name,
+            avatar_url: profile.avatar_url,
+            created_at: profile.created_at,
+        }
+    }
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct IdentitySessionResponse {
+    /// Session to send along with the signed data to `POST /registrations/github`
+    pub session: String,
+    /// Expiration date of the session, as a UNIX timestamp in seconds
+    pub expires_at: u64,
+    pub profile: UserProfile,
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct GithubUserUnregistrationResponse {
+    pub transaction_hash: HexFieldElement,
+}
+
 #[derive(Deserialize, JsonSchema)]
 #[serde(crate = "rocket::serde")]
-pub struct GithubUserRegistrationRequest<'r> {
-    pub authorization_code: &'r str,
+pub struct GithubUserMigrationRequest<'r> {
+    /// OAuth authorization code, required unless `access_token` or `session` is given
+    pub authorization_code: Option<&'r str>,
+    /// User access token, e.g. obtained through the device authorization flow
+    pub access_token: Option<&'r str>,
+    /// Identity session opened by `GET /oauth/github/callback`
+    pub session: Option<&'r str>,
+    /// OAuth state of the authorization request issued by `GET /oauth/github/authorize`,
+    /// sent back along with the authorization code
+    pub state: Option<&'r str>,
+    /// Account the GitHub identity is currently registered with
+    pub old_account_address: HexFieldElement,
+    /// Account the GitHub identity must be registered with
     pub account_address: HexFieldElement,
+    /// Migration message signed by the new account
     pub signed_data: SignedData,
+    /// Migration message signed by the old account, if it is still available
+    pub old_signed_data: Option<SignedData>,
+    /// Nonce of the registration challenge, as returned by `GET /registrations/challenge`
+    /// for `account_address`
+    pub nonce: &'r str,
+    /// Name of the badge registry of the identifier, the default registry if not given
+    pub registry: Option<&'r str>,
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct GithubUserMigrationResponse {
+    pub transaction_hash: HexFieldElement,
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct RegistrationChallengeResponse {
+    pub nonce: String,
+    /// Expiration date of the nonce, as a UNIX timestamp in seconds
+    pub expires_at: u64,
+}
+
+#[derive(Serialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(crate = "rocket::serde", rename_all = "snake_case")]
+pub enum RegistrationTransactionStatus {
+    Pending,
+    AcceptedOnL2,
+    AcceptedOnL1,
+    Rejected,
 }
 
 #[derive(Serialize, JsonSchema)]
 #[serde(crate = "rocket::serde")]
-pub struct GithubUserRegistrationResponse {
+pub struct RegistrationStatusResponse {
     pub transaction_hash: HexFieldElement,
+    pub status: RegistrationTransactionStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+}
+
+impl RegistrationStatusResponse {
+    pub fn new(transaction_hash: HexFieldElement, status: TransactionStatus) -> Self {
+        let (status, failure_reason) = match status {
+            // sent by the service, but not received by the network yet
+            TransactionStatus::NotReceived | TransactionStatus::Pending => {
+                (RegistrationTransactionStatus::Pending, None)
+            }
+            TransactionStatus::AcceptedOnL2 => (RegistrationTransactionStatus::AcceptedOnL2, None),
+            TransactionStatus::AcceptedOnL1 => (RegistrationTransactionStatus::AcceptedOnL1, None),
+            TransactionStatus::Rejected { reason } => {
+                (RegistrationTransactionStatus::Rejected, reason)
+            }
+        };
+
+        RegistrationStatusResponse {
+            transaction_hash,
+            status,
+            failure_reason,
+        }
+    }
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct DeviceAuthorizationResponse {
+    /// Code to poll `POST /oauth/github/device/token` with
+    pub device_code: String,
+    /// Code the user must enter at `verification_uri`
+    pub user_code: String,
+    pub verification_uri: String,
+    /// Lifetime of the codes, in seconds
+    pub expires_in: u64,
+    /// Minimum interval between two polls, in seconds
+    pub interval: u64,
+}
+
+impl From<DeviceAuthorization> for DeviceAuthorizationResponse {
+    fn from(authorization: DeviceAuthorization) -> Self {
+        DeviceAuthorizationResponse {
+            device_code: authorization.device_code,
+            user_code: authorization.user_code,
+            verification_uri: authorization.verification_uri,
+            expires_in: authorization.expires_in.as_secs(),
+            interval: authorization.interval.as_secs(),
+        }
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct DeviceTokenRequest<'r> {
+    pub device_code: &'r str,
+}
+
+#[derive(Serialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(crate = "rocket::serde", rename_all = "snake_case")]
+pub enum DeviceTokenStatus {
+    Pending,
+    SlowDown,
+    Authorized,
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct DeviceTokenResponse {
+    pub status: DeviceTokenStatus,
+    /// New minimum interval between two polls, in seconds, when polling too fast
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<u64>,
+    /// Access token granted once the user authorized the device
+    #[serde(flatten)]
+    pub token: Option<AccessTokenResponse>,
+}
+
+impl From<DeviceAuthorizationStatus> for DeviceTokenResponse {
+    fn from(status: DeviceAuthorizationStatus) -> Self {
+        match status {
+            DeviceAuthorizationStatus::Pending => DeviceTokenResponse {
+                status: DeviceTokenStatus::Pending,
+                interval: None,
+                token: None,
+            },
+            DeviceAuthorizationStatus::SlowDown { interval } => DeviceTokenResponse {
+                status: DeviceTokenStatus::SlowDown,
+                interval: Some(interval.as_secs()),
+                token: None,
+            },
+            DeviceAuthorizationStatus::Authorized(grant) => DeviceTokenResponse {
+                status: DeviceTokenStatus::Authorized,
+                interval: None,
+                token: Some(grant.into()),
+            },
+        }
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct RefreshTokenRequest<'r> {
+    pub refresh_token: &'r str,
+}
+
+#[derive(Serialize, JsonSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct AccessTokenResponse {
+    /// Token to send as `access_token` to the registration endpoints
+    pub access_token: String,
+    /// Lifetime of the access token in seconds, unset if it never expires
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_in: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// Lifetime of the refresh token, in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_token_expires_in: Option<u64>,
+}
+
+impl From<TokenGrant> for AccessTokenResponse {
+    fn from(grant: TokenGrant) -> Self {
+        AccessTokenResponse {
+            access_token: grant.access_token.to_string(),
+            expires_in: grant.expires_in.map(|expires_in| expires_in.as_secs()),
+            refresh_token: grant.refresh_token,
+            refresh_token_expires_in: grant
+                .refresh_token_expires_in
+                .map(|expires_in| expires_in.as_secs()),
+        }
+    }
 }
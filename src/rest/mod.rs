@@ -1,5 +1,6 @@
 pub mod cors;
 pub mod health;
+pub mod oauth;
 pub mod registrations;
 pub mod router;
 
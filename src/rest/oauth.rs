@@ -0,0 +1,578 @@
+use std::time::UNIX_EPOCH;
+
+use http_api_problem::HttpApiProblem;
+use http_api_problem::StatusCode;
+use rocket::{response::Redirect, serde::json::Json, State};
+use rocket_okapi::openapi;
+
+use crate::{
+    application::registerer::Registerer,
+    domain::{
+        errors::{
+            AuthenticationError, DeviceAuthorizationError, IdentificationError,
+            IdentitySessionError, RegistrationError,
+        },
+        services::device_authorization_provider::DeviceAuthorizationProvider,
+        value_objects::Authorization,
+    },
+    infrastructure::{github_client::GitHubClient, starknet_client::StarkNetClient},
+};
+
+use super::dto::AccessTokenResponse;
+use super::dto::DeviceAuthorizationResponse;
+use super::dto::DeviceTokenRequest;
+use super::dto::DeviceTokenResponse;
+use super::dto::IdentitySessionResponse;
+use super::dto::RefreshTokenRequest;
+
+type GithubStarknetRegisterer = dyn Registerer<GitHubClient, StarkNetClient>;
+type GithubDeviceAuthorizationProvider = Option<Box<dyn DeviceAuthorizationProvider>>;
+
+/// Issues an authorization request, and redirects the user to the GitHub authorization page.
+/// The authorization code GitHub sends back must be registered along with the `state`.
+#[openapi(tag = "OAuth")]
+#[get("/oauth/github/authorize")]
+pub async fn authorize_github_user(
+    github_starknet_registerer: &State<Box<GithubStarknetRegisterer>>,
+) -> Result<Redirect, HttpApiProblem> {
+    let authorization_url = github_starknet_registerer
+        .new_authorization_url()
+        .await
+        .ok_or_else(|| {
+            HttpApiProblem::new(StatusCode::NOT_FOUND)
+                .title("Unsupported authorization flow")
+                .detail("GitHub authorization requests are not enabled")
+        })?;
+
+    Ok(Redirect::to(authorization_url))
+}
+
+/// Receives the user sent back by GitHub after authorization, identifies them, and opens an
+/// identity session. The session is then registered with the signed data of the account.
+#[openapi(tag = "OAuth")]
+#[get("/oauth/github/callback?<code>&<state>&<error>")]
+pub async fn github_callback(
+    code: Option<&str>,
+    state: Option<&str>,
+    error: Option<&str>,
+    github_starknet_registerer: &State<Box<GithubStarknetRegisterer>>,
+) -> Result<Json<IdentitySessionResponse>, HttpApiProblem> {
+    if let Some(error) = error {
+        warn!("GitHub authorization failed. Error: {}", error);
+        return Err(HttpApiProblem::new(StatusCode::FORBIDDEN)
+            .title("Authorization denied")
+            .detail(format!("GitHub authorization failed: {}", error)));
+    }
+    let code = code.ok_or_else(|| {
+        HttpApiProblem::new(StatusCode::BAD_REQUEST)
+            .title("Missing code")
+            .detail("GitHub did not send an authorization code")
+    })?;
+
+    let session = github_starknet_registerer
+        .open_session(Authorization::Code {
+            code: code.to_string(),
+            state: state.map(ToString::to_string),
+        })
+        .await
+        .map_err(|e| callback_problem(e, code))?;
+
+    info!(
+        "opened identity session of GitHub user {}",
+        session.profile.login
+    );
+    Ok(Json(IdentitySessionResponse {
+        session: session.id,
+        expires_at: session
+            .expires_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        profile: session.profile.into(),
+    }))
+}
+
+fn callback_problem(error: RegistrationError, authorization_code: &str) -> HttpApiProblem {
+    match error {
+        RegistrationError::Session(IdentitySessionError::Unsupported) => {
+            super::registrations::disabled_session_problem("GitHub")
+        }
+        RegistrationError::Authentication(e) => {
+            warn!(
+                "Failed to get new GitHub access token from code {}. Error: {:?}",
+                authorization_code, e
+            );
+            HttpApiProblem::new(StatusCode::UNAUTHORIZED)
+                .title("Invalid GitHub code")
+                .detail(format!(
+                    "Failed to get new GitHub access token from code {}",
+                    authorization_code
+                ))
+        }
+        RegistrationError::State(e) => {
+            warn!("Invalid GitHub OAuth state. Error: {:?}", e);
+            HttpApiProblem::new(StatusCode::UNAUTHORIZED)
+                .title("Invalid state")
+                .detail(format!(
+                    "{}, authorization requests must start from GET /oauth/github/authorize",
+                    e
+                ))
+        }
+        error @ (RegistrationError::AccountTooRecent
+        | RegistrationError::NotEnoughRepositories
+        | RegistrationError::NotEnoughFollowers
+        | RegistrationError::AccountSuspended
+        | RegistrationError::AccountDenied
+        | RegistrationError::NotAMember) => {
+            warn!("GitHub account is not eligible. Error: {:?}", error);
+            HttpApiProblem::new(StatusCode::FORBIDDEN)
+                .title("Account not eligible")
+                .detail(format!("{}", error))
+                .value("reason", &error.kind())
+        }
+        RegistrationError::Identification(IdentificationError::Unauthorized) => {
+            warn!("GitHub access token was rejected");
+            HttpApiProblem::new(StatusCode::UNAUTHORIZED)
+                .title("Invalid GitHub access token")
+                .detail("GitHub access token is invalid, expired or revoked")
+        }
+        e => {
+            error!("Failed to identify GitHub user. Error: {:?}", e);
+            HttpApiProblem::new(StatusCode::INTERNAL_SERVER_ERROR)
+                .title("Identification error")
+                .detail("Failed to identify GitHub user")
+        }
+    }
+}
+
+fn device_flow(
+    provider: &State<GithubDeviceAuthorizationProvider>,
+) -> Result<&dyn DeviceAuthorizationProvider, HttpApiProblem> {
+    provider.inner().as_deref().ok_or_else(|| {
+        HttpApiProblem::new(StatusCode::NOT_FOUND)
+            .title("Unsupported authorization flow")
+            .detail("GitHub device authorization flow is not enabled")
+    })
+}
+
+/// Starts a device authorization. The user must enter the returned user code at the
+/// verification URI, while the client polls `POST /oauth/github/device/token`.
+#[openapi(tag = "OAuth")]
+#[post("/oauth/github/device")]
+pub async fn start_github_device_authorization(
+    device_authorization_provider: &State<GithubDeviceAuthorizationProvider>,
+) -> Result<Json<DeviceAuthorizationResponse>, HttpApiProblem> {
+    let authorization = device_flow(device_authorization_provider)?
+        .start_device_authorization()
+        .await
+        .map_err(|e| {
+            error!(
+                "Failed to start GitHub device authorization. Error: {:?}",
+                e
+            );
+            HttpApiProblem::new(StatusCode::INTERNAL_SERVER_ERROR)
+                .title("Device authorization error")
+                .detail("Failed to start GitHub device authorization")
+        })?;
+
+    Ok(Json(authorization.into()))
+}
+
+/// Polls a device authorization, until the user authorizes it.
+#[openapi(tag = "OAuth")]
+#[post("/oauth/github/device/token", format = "json", data = "<request>")]
+pub async fn poll_github_device_authorization(
+    request: Json<DeviceTokenRequest<'_>>,
+    device_authorization_provider: &State<GithubDeviceAuthorizationProvider>,
+) -> Result<Json<DeviceTokenResponse>, HttpApiProblem> {
+    let status = device_flow(device_authorization_provider)?
+        .poll_device_authorization(request.device_code)
+        .await
+        .map_err(|e| match e {
+            DeviceAuthorizationError::Expired => HttpApiProblem::new(StatusCode::GONE)
+                .title("Device authorization expired")
+                .detail("Device code has expired, a new device authorization must be started"),
+            DeviceAuthorizationError::Denied => HttpApiProblem::new(StatusCode::FORBIDDEN)
+                .title("Device authorization denied")
+                .detail("User denied the device authorization"),
+            DeviceAuthorizationError::Authentication(AuthenticationError::Rejected(reason)) => {
+                warn!("GitHub rejected device code. Error: {}", reason);
+                HttpApiProblem::new(StatusCode::BAD_REQUEST)
+                    .title("Invalid device code")
+                    .detail(format!("GitHub rejected the device code: {}", reason))
+            }
+            DeviceAuthorizationError::Authentication(e) => {
+                error!("Failed to poll GitHub device authorization. Error: {:?}", e);
+                HttpApiProblem::new(StatusCode::INTERNAL_SERVER_ERROR)
+                    .title("Device authorization error")
+                    .detail("Failed to poll GitHub device authorization")
+            }
+        })?;
+
+    Ok(Json(status.into()))
+}
+
+/// Exchanges the refresh token of an expiring GitHub App user access token for a new one.
+#[openapi(tag = "OAuth")]
+#[post("/oauth/github/token/refresh", format = "json", data = "<request>")]
+pub async fn refresh_github_access_token(
+    request: Json<RefreshTokenRequest<'_>>,
+    device_authorization_provider: &State<GithubDeviceAuthorizationProvider>,
+) -> Result<Json<AccessTokenResponse>, HttpApiProblem> {
+    let grant = device_flow(device_authorization_provider)?
+        .refresh_access_token(request.refresh_token)
+        .await
+        .map_err(|e| match e {
+            AuthenticationError::Rejected(reason) => {
+                warn!("GitHub rejected refresh token. Error: {}", reason);
+                HttpApiProblem::new(StatusCode::UNAUTHORIZED)
+                    .title("Invalid refresh token")
+                    .detail(format!("GitHub rejected the refresh token: {}", reason))
+            }
+            e => {
+                error!("Failed to refresh GitHub access token. Error: {:?}", e);
+                HttpApiProblem::new(StatusCode::INTERNAL_SERVER_ERROR)
+                    .title("Token refresh error")
+                    .detail("Failed to refresh GitHub access token")
+            }
+        })?;
+
+    Ok(Json(grant.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use claim::assert_some_eq;
+    use mockall::{mock, predicate::eq};
+    use rocket::{
+        http::{ContentType, Status},
+        local::blocking::Client,
+        serde::json::serde_json::json,
+    };
+
+    use crate::{
+        application::registerer::Registerer,
+        domain::{
+            errors::{
+                AuthenticationError, AuthorizationStateError, DeviceAuthorizationError,
+                RegistrationError,
+            },
+            services::{
+                device_authorization_provider::DeviceAuthorizationProvider,
+                onchain_registry::OnChainRegistry,
+            },
+            value_objects::{
+                AccessToken, Authorization, Challenge, DeviceAuthorization,
+                DeviceAuthorizationStatus, Identity, IdentityProfile, IdentitySession, Nonce,
+                RegistrationOutcome, TokenGrant, TransactionStatus,
+            },
+        },
+        infrastructure::{
+            github_client::GitHubClient, in_memory_idempotency_store::InMemoryIdempotencyStore,
+            starknet_client::StarkNetClient,
+        },
+        rest,
+    };
+
+    mock! {
+        MyRegisterer {}
+        #[async_trait]
+        impl Registerer<GitHubClient, StarkNetClient> for MyRegisterer {
+            async fn new_challenge(&self, account_address: <StarkNetClient as OnChainRegistry>::AccountAddress) -> Challenge;
+
+            async fn new_authorization_url(&self) -> Option<String>;
+
+            async fn open_session(
+                &self,
+                authorization: Authorization,
+            ) -> Result<IdentitySession, RegistrationError>;
+
+            async fn register_contributor(
+                &self,
+                authorization: Authorization,
+                account_address: <StarkNetClient as OnChainRegistry>::AccountAddress,
+                signed_data: <StarkNetClient as OnChainRegistry>::SignedData,
+                nonce: Nonce,
+            ) -> Result<(RegistrationOutcome<<StarkNetClient as OnChainRegistry>::TransactionHash>, IdentityProfile), RegistrationError>;
+
+            async fn unregister_contributor(
+                &self,
+                authorization: Authorization,
+                account_address: <StarkNetClient as OnChainRegistry>::AccountAddress,
+                signed_data: <StarkNetClient as OnChainRegistry>::SignedData,
+                nonce: Nonce,
+            ) -> Result<<StarkNetClient as OnChainRegistry>::TransactionHash, RegistrationError>;
+
+            async fn migrate_contributor(
+                &self,
+                authorization: Authorization,
+                old_account_address: <StarkNetClient as OnChainRegistry>::AccountAddress,
+                new_account_address: <StarkNetClient as OnChainRegistry>::AccountAddress,
+                new_signed_data: <StarkNetClient as OnChainRegistry>::SignedData,
+                old_signed_data: Option<<StarkNetClient as OnChainRegistry>::SignedData>,
+                nonce: Nonce,
+            ) -> Result<<StarkNetClient as OnChainRegistry>::TransactionHash, RegistrationError>;
+
+            async fn get_registration_status(
+                &self,
+                transaction_hash: <StarkNetClient as OnChainRegistry>::TransactionHash,
+            ) -> Result<TransactionStatus, RegistrationError>;
+        }
+    }
+
+    mock! {
+        MyDeviceAuthorizationProvider {}
+        #[async_trait]
+        impl DeviceAuthorizationProvider for MyDeviceAuthorizationProvider {
+            async fn start_device_authorization(&self) -> Result<DeviceAuthorization, AuthenticationError>;
+
+            async fn poll_device_authorization(
+                &self,
+                device_code: &str,
+            ) -> Result<DeviceAuthorizationStatus, DeviceAuthorizationError>;
+
+            async fn refresh_access_token(
+                &self,
+                refresh_token: &str,
+            ) -> Result<TokenGrant, AuthenticationError>;
+        }
+    }
+
+    fn client(provider: Option<MockMyDeviceAuthorizationProvider>) -> Client {
+        client_with_registerer(MockMyRegisterer::new(), provider)
+    }
+
+    fn client_with_registerer(
+        registerer: MockMyRegisterer,
+        provider: Option<MockMyDeviceAuthorizationProvider>,
+    ) -> Client {
+        let router = rest::router::new_with_providers(
+            Box::new(registerer) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            None,
+            None,
+            provider.map(|provider| Box::new(provider) as Box<dyn DeviceAuthorizationProvider>),
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        Client::tracked(router).expect("valid rocket instance")
+    }
+
+    fn poll(client: &Client) -> rocket::local::blocking::LocalResponse<'_> {
+        client
+            .post(uri!("/oauth/github/device/token"))
+            .header(ContentType::JSON)
+            .body(json!({ "device_code": "foo-device-code" }).to_string())
+            .dispatch()
+    }
+
+    #[test]
+    fn test_authorize_github_user() {
+        let mut registerer_mock = MockMyRegisterer::new();
+        registerer_mock
+            .expect_new_authorization_url()
+            .times(1)
+            .returning(|| {
+                Some("https://github.com/login/oauth/authorize?state=foo-state".to_string())
+            });
+
+        let client = client_with_registerer(registerer_mock, None);
+        let response = client.get(uri!("/oauth/github/authorize")).dispatch();
+
+        assert_eq!(response.status(), Status::SeeOther);
+        assert_eq!(
+            response.headers().get_one("Location"),
+            Some("https://github.com/login/oauth/authorize?state=foo-state")
+        );
+    }
+
+    #[test]
+    fn test_authorize_github_user_disabled() {
+        let mut registerer_mock = MockMyRegisterer::new();
+        registerer_mock
+            .expect_new_authorization_url()
+            .returning(|| None);
+
+        let client = client_with_registerer(registerer_mock, None);
+        let response = client.get(uri!("/oauth/github/authorize")).dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_github_callback() {
+        let mut registerer_mock = MockMyRegisterer::new();
+        registerer_mock
+            .expect_open_session()
+            .with(eq(Authorization::Code {
+                code: "foo-code".to_string(),
+                state: Some("foo-state".to_string()),
+            }))
+            .times(1)
+            .returning(|_| {
+                Ok(IdentitySession {
+                    id: "foo-session".to_string(),
+                    identity: Identity::GitHubId(42.into()),
+                    profile: IdentityProfile {
+                        login: "octocat".to_string(),
+                        name: None,
+                        avatar_url: None,
+                        created_at: None,
+                        public_repos: None,
+                        followers: None,
+                        suspended: false,
+                    },
+                    expires_at: UNIX_EPOCH + Duration::from_secs(900),
+                })
+            });
+
+        let client = client_with_registerer(registerer_mock, None);
+        let response = client
+            .get("/oauth/github/callback?code=foo-code&state=foo-state")
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_some_eq!(
+            response.into_string(),
+            "{\"session\":\"foo-session\",\"expires_at\":900,\"profile\":{\"login\":\"octocat\"}}"
+        );
+    }
+
+    #[test]
+    fn test_github_callback_with_unknown_state() {
+        let mut registerer_mock = MockMyRegisterer::new();
+        registerer_mock
+            .expect_open_session()
+            .returning(|_| Err(RegistrationError::State(AuthorizationStateError::Unknown)));
+
+        let client = client_with_registerer(registerer_mock, None);
+        let response = client
+            .get("/oauth/github/callback?code=foo-code&state=foo-state")
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Unauthorized);
+    }
+
+    #[test]
+    fn test_github_callback_denied_by_user() {
+        let mut registerer_mock = MockMyRegisterer::new();
+        registerer_mock.expect_open_session().never();
+
+        let client = client_with_registerer(registerer_mock, None);
+        let response = client
+            .get("/oauth/github/callback?error=access_denied&state=foo-state")
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Forbidden);
+    }
+
+    #[test]
+    fn test_start_device_authorization() {
+        let mut provider_mock = MockMyDeviceAuthorizationProvider::new();
+        provider_mock
+            .expect_start_device_authorization()
+            .times(1)
+            .returning(|| {
+                Ok(DeviceAuthorization {
+                    device_code: "foo-device-code".to_string(),
+                    user_code: "WDJB-MJHT".to_string(),
+                    verification_uri: "https://github.com/login/device".to_string(),
+                    expires_in: Duration::from_secs(900),
+                    interval: Duration::from_secs(5),
+                })
+            });
+
+        let client = client(Some(provider_mock));
+        let response = client.post(uri!("/oauth/github/device")).dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_some_eq!(
+            response.into_string(),
+            "{\"device_code\":\"foo-device-code\",\"user_code\":\"WDJB-MJHT\",\"verification_uri\":\"https://github.com/login/device\",\"expires_in\":900,\"interval\":5}"
+        );
+    }
+
+    #[test]
+    fn test_device_authorization_disabled() {
+        let client = client(None);
+        let response = client.post(uri!("/oauth/github/device")).dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_poll_pending_device_authorization() {
+        let mut provider_mock = MockMyDeviceAuthorizationProvider::new();
+        provider_mock
+            .expect_poll_device_authorization()
+            .with(eq("foo-device-code"))
+            .times(1)
+            .returning(|_| Ok(DeviceAuthorizationStatus::Pending));
+
+        let client = client(Some(provider_mock));
+        let response = poll(&client);
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_some_eq!(response.into_string(), "{\"status\":\"pending\"}");
+    }
+
+    #[test]
+    fn test_poll_authorized_device_authorization() {
+        let mut provider_mock = MockMyDeviceAuthorizationProvider::new();
+        provider_mock
+            .expect_poll_device_authorization()
+            .returning(|_| {
+                Ok(DeviceAuthorizationStatus::Authorized(TokenGrant {
+                    access_token: AccessToken::from("ghu_token".to_string()),
+                    expires_in: Some(Duration::from_secs(28800)),
+                    refresh_token: Some("ghr_token".to_string()),
+                    refresh_token_expires_in: Some(Duration::from_secs(15811200)),
+                }))
+            });
+
+        let client = client(Some(provider_mock));
+        let response = poll(&client);
+
+        assert_eq!(response.status(), Status::Ok);
+        assert_some_eq!(
+            response.into_string(),
+            "{\"status\":\"authorized\",\"access_token\":\"ghu_token\",\"expires_in\":28800,\"refresh_token\":\"ghr_token\",\"refresh_token_expires_in\":15811200}"
+        );
+    }
+
+    #[test]
+    fn test_poll_expired_device_authorization() {
+        let mut provider_mock = MockMyDeviceAuthorizationProvider::new();
+        provider_mock
+            .expect_poll_device_authorization()
+            .returning(|_| Err(DeviceAuthorizationError::Expired));
+
+        let client = client(Some(provider_mock));
+        let response = poll(&client);
+
+        assert_eq!(response.status(), Status::Gone);
+    }
+
+    #[test]
+    fn test_refresh_access_token_with_bad_refresh_token() {
+        let mut provider_mock = MockMyDeviceAuthorizationProvider::new();
+        provider_mock
+            .expect_refresh_access_token()
+            .with(eq("ghr_token"))
+            .returning(|_| {
+                Err(AuthenticationError::Rejected(
+                    "bad_refresh_token".to_string(),
+                ))
+            });
+
+        let client = client(Some(provider_mock));
+        let response = client
+            .post(uri!("/oauth/github/token/refresh"))
+            .header(ContentType::JSON)
+            .body(json!({ "refresh_token": "ghr_token" }).to_string())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Unauthorized);
+    }
+}
@@ -1,105 +1,697 @@
+use std::{collections::HashMap, time::UNIX_EPOCH};
+
 use crate::{
     application::registerer::Registerer,
-    domain::errors::RegistrationError,
-    infrastructure::{github_client::GitHubClient, starknet_client::StarkNetClient},
+    domain::{
+        errors::{IdentificationError, IdentitySessionError, RegistrationError, SignatureError},
+        services::{idempotency_store::IdempotencyStore, identity_provider::IdentityProvider},
+        value_objects::{
+            AccessToken, Authorization, IdempotentRequest, Nonce, RegistrationOutcome,
+        },
+    },
+    infrastructure::{
+        discord_client::DiscordClient, github_client::GitHubClient, gitlab_client::GitLabClient,
+        starknet_client::StarkNetClient,
+    },
 };
 use http_api_problem::HttpApiProblem;
 use http_api_problem::StatusCode;
 use rocket::{serde::json::Json, State};
 use rocket_okapi::openapi;
+use starknet::core::types::FieldElement;
 
-use super::dto::GithubUserRegistrationRequest;
-use super::dto::GithubUserRegistrationResponse;
+use super::dto::GithubUserMigrationRequest;
+use super::dto::GithubUserMigrationResponse;
+use super::dto::GithubUserUnregistrationResponse;
+use super::dto::HexFieldElement;
+use super::dto::IdempotencyKey;
+use super::dto::RegistrationChallengeResponse;
+use super::dto::RegistrationStatusResponse;
+use super::dto::UserRegistrationRequest;
+use super::dto::UserRegistrationResponse;
 
 type GithubStarknetRegisterer = dyn Registerer<GitHubClient, StarkNetClient>;
+type GitlabStarknetRegisterer = dyn Registerer<GitLabClient, StarkNetClient>;
+type DiscordStarknetRegisterer = dyn Registerer<DiscordClient, StarkNetClient>;
+
+const GITHUB: &str = "GitHub";
+const GITLAB: &str = "GitLab";
+const DISCORD: &str = "Discord";
+
+/// Stores the responses of `POST /registrations/<provider>`, by idempotency key
+pub type RegistrationIdempotencyStore =
+    dyn IdempotencyStore<Result<UserRegistrationResponse, HttpApiProblem>>;
+
+/// Registerers of the badge registries other than the default one, by registry name.
+/// Requests select one of them with their `registry` field.
+#[derive(Default)]
+pub struct BadgeRegistries {
+    pub github: HashMap<String, Box<GithubStarknetRegisterer>>,
+    pub gitlab: HashMap<String, Box<GitlabStarknetRegisterer>>,
+    pub discord: HashMap<String, Box<DiscordStarknetRegisterer>>,
+}
+
+/// Issues a one-time nonce, to be signed by the account at `account_address` only.
+#[openapi(tag = "Registrations")]
+#[get("/registrations/challenge?<account_address>")]
+pub async fn new_registration_challenge(
+    account_address: &str,
+    github_starknet_registerer: &State<Box<GithubStarknetRegisterer>>,
+) -> Result<Json<RegistrationChallengeResponse>, HttpApiProblem> {
+    let account_address = FieldElement::from_hex_be(account_address).map_err(|_| {
+        HttpApiProblem::new(StatusCode::BAD_REQUEST)
+            .title("Invalid account address")
+            .detail(format!(
+                "Account address {} is not a valid hexadecimal value",
+                account_address
+            ))
+    })?;
+    let challenge = github_starknet_registerer
+        .new_challenge(account_address)
+        .await;
+
+    Ok(Json(RegistrationChallengeResponse {
+        nonce: challenge.nonce.to_string(),
+        expires_at: challenge
+            .expires_at
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+    }))
+}
 
 #[openapi(tag = "Registrations")]
 #[post("/registrations/github", format = "json", data = "<registration>")]
 pub async fn register_github_user(
-    registration: Json<GithubUserRegistrationRequest<'_>>,
+    registration: Json<UserRegistrationRequest<'_>>,
+    idempotency_key: Option<IdempotencyKey>,
     github_starknet_registerer: &State<Box<GithubStarknetRegisterer>>,
-) -> Result<Json<GithubUserRegistrationResponse>, HttpApiProblem> {
-    let result = github_starknet_registerer
+    badge_registries: &State<BadgeRegistries>,
+    idempotency_store: &State<Box<RegistrationIdempotencyStore>>,
+) -> Result<Json<UserRegistrationResponse>, HttpApiProblem> {
+    let github_starknet_registerer = select_registerer(
+        &***github_starknet_registerer,
+        &badge_registries.github,
+        registration.registry,
+    )?;
+
+    register_idempotently(
+        GITHUB,
+        &registration,
+        idempotency_key,
+        github_starknet_registerer,
+        idempotency_store,
+    )
+    .await
+}
+
+#[openapi(tag = "Registrations")]
+#[post("/registrations/gitlab", format = "json", data = "<registration>")]
+pub async fn register_gitlab_user(
+    registration: Json<UserRegistrationRequest<'_>>,
+    idempotency_key: Option<IdempotencyKey>,
+    gitlab_starknet_registerer: &State<Option<Box<GitlabStarknetRegisterer>>>,
+    badge_registries: &State<BadgeRegistries>,
+    idempotency_store: &State<Box<RegistrationIdempotencyStore>>,
+) -> Result<Json<UserRegistrationResponse>, HttpApiProblem> {
+    let gitlab_starknet_registerer = gitlab_starknet_registerer
+        .inner()
+        .as_deref()
+        .ok_or_else(|| disabled_provider_problem(GITLAB))?;
+    let gitlab_starknet_registerer = select_registerer(
+        gitlab_starknet_registerer,
+        &badge_registries.gitlab,
+        registration.registry,
+    )?;
+
+    register_idempotently(
+        GITLAB,
+        &registration,
+        idempotency_key,
+        gitlab_starknet_registerer,
+        idempotency_store,
+    )
+    .await
+}
+
+#[openapi(tag = "Registrations")]
+#[post("/registrations/discord", format = "json", data = "<registration>")]
+pub async fn register_discord_user(
+    registration: Json<UserRegistrationRequest<'_>>,
+    idempotency_key: Option<IdempotencyKey>,
+    discord_starknet_registerer: &State<Option<Box<DiscordStarknetRegisterer>>>,
+    badge_registries: &State<BadgeRegistries>,
+    idempotency_store: &State<Box<RegistrationIdempotencyStore>>,
+) -> Result<Json<UserRegistrationResponse>, HttpApiProblem> {
+    let discord_starknet_registerer = discord_starknet_registerer
+        .inner()
+        .as_deref()
+        .ok_or_else(|| disabled_provider_problem(DISCORD))?;
+    let discord_starknet_registerer = select_registerer(
+        discord_starknet_registerer,
+        &badge_registries.discord,
+        registration.registry,
+    )?;
+
+    register_idempotently(
+        DISCORD,
+        &registration,
+        idempotency_key,
+        discord_starknet_registerer,
+        idempotency_store,
+    )
+    .await
+}
+
+pub(super) fn disabled_session_problem(provider: &str) -> HttpApiProblem {
+    HttpApiProblem::new(StatusCode::NOT_FOUND)
+        .title("Unsupported authorization flow")
+        .detail(format!("{} identity sessions are not enabled", provider))
+}
+
+fn disabled_provider_problem(provider: &str) -> HttpApiProblem {
+    HttpApiProblem::new(StatusCode::NOT_FOUND)
+        .title("Unsupported identity provider")
+        .detail(format!("{} registrations are not enabled", provider))
+}
+
+/// Registerer of the `registry` badge registry, or `default_registerer` if no registry is given.
+fn select_registerer<'r, P: IdentityProvider>(
+    default_registerer: &'r dyn Registerer<P, StarkNetClient>,
+    registerers: &'r HashMap<String, Box<dyn Registerer<P, StarkNetClient>>>,
+    registry: Option<&str>,
+) -> Result<&'r dyn Registerer<P, StarkNetClient>, HttpApiProblem> {
+    let registry = match registry {
+        Some(registry) => registry,
+        None => return Ok(default_registerer),
+    };
+
+    registerers
+        .get(registry)
+        .map(|registerer| registerer.as_ref())
+        .ok_or_else(|| {
+            HttpApiProblem::new(StatusCode::BAD_REQUEST)
+                .title("Unknown registry")
+                .detail(format!("Badge registry {} is not served", registry))
+        })
+}
+
+async fn register_idempotently<P: IdentityProvider>(
+    provider: &str,
+    registration: &UserRegistrationRequest<'_>,
+    idempotency_key: Option<IdempotencyKey>,
+    registerer: &dyn Registerer<P, StarkNetClient>,
+    idempotency_store: &RegistrationIdempotencyStore,
+) -> Result<Json<UserRegistrationResponse>, HttpApiProblem> {
+    let idempotency_key = match (idempotency_key, registration.registry) {
+        // keys are scoped by identity provider and badge registry
+        (Some(idempotency_key), None) => format!("{}:{}", provider, idempotency_key.as_str()),
+        (Some(idempotency_key), Some(registry)) => {
+            format!("{}:{}:{}", provider, registry, idempotency_key.as_str())
+        }
+        (None, _) => return register(provider, registration, registerer).await.map(Json),
+    };
+
+    let fingerprint = registration.fingerprint();
+    match idempotency_store
+        .begin(&idempotency_key, &fingerprint)
+        .await
+    {
+        IdempotentRequest::New => (),
+        IdempotentRequest::InProgress => {
+            warn!(
+                "Registration with idempotency key {} is already in progress",
+                idempotency_key
+            );
+            return Err(HttpApiProblem::new(StatusCode::CONFLICT)
+                .title("Request in progress")
+                .detail(format!(
+                    "A registration with idempotency key {} is already in progress",
+                    idempotency_key
+                )));
+        }
+        IdempotentRequest::Completed(response) => {
+            info!(
+                "replaying registration response of idempotency key {}",
+                idempotency_key
+            );
+            return response.map(Json);
+        }
+        IdempotentRequest::Mismatch => {
+            warn!(
+                "Idempotency key {} has been reused for another registration",
+                idempotency_key
+            );
+            return Err(HttpApiProblem::new(StatusCode::UNPROCESSABLE_ENTITY)
+                .title("Idempotency key reused")
+                .detail(format!(
+                    "Idempotency key {} has already been used for another registration",
+                    idempotency_key
+                )));
+        }
+    }
+
+    let response = register(provider, registration, registerer).await;
+    idempotency_store
+        .complete(&idempotency_key, &fingerprint, response.clone())
+        .await;
+
+    response.map(Json)
+}
+
+async fn register<P: IdentityProvider>(
+    provider: &str,
+    registration: &UserRegistrationRequest<'_>,
+    registerer: &dyn Registerer<P, StarkNetClient>,
+) -> Result<UserRegistrationResponse, HttpApiProblem> {
+    let (outcome, profile) = registerer
         .register_contributor(
-            registration.authorization_code.to_string(),
+            parse_authorization(
+                registration.authorization_code,
+                registration.access_token,
+                registration.session,
+                registration.state,
+            )?,
             registration.account_address.into(),
-            registration.signed_data.into(),
+            registration.signed_data.clone().into(),
+            parse_nonce(registration.nonce)?,
         )
-        .await;
+        .await
+        .map_err(|e| {
+            into_problem(
+                e,
+                provider,
+                registration.authorization_code.unwrap_or_default(),
+                registration.account_address,
+                registration.nonce,
+            )
+        })?;
 
-    let transaction_hash = match result {
-        Ok(transaction_hash) => transaction_hash,
-        Err(e) => match e {
-            RegistrationError::Authentication(e) => {
-                warn!(
-                    "Failed to get new GitHub access token from code {}. Error: {:?}",
-                    registration.authorization_code, e
-                );
-                return Err(HttpApiProblem::new(StatusCode::UNAUTHORIZED)
-                    .title("Invalid GitHub code")
-                    .detail(format!(
-                        "Failed to get new GitHub access token from code {}",
-                        registration.authorization_code
-                    )));
+    let response = match outcome {
+        RegistrationOutcome::Submitted(transaction_hash) => {
+            info!(
+                "successfully registered user with account {}",
+                registration.account_address
+            );
+            UserRegistrationResponse {
+                transaction_hash: Some(transaction_hash.into()),
+                already_registered: false,
+                profile: profile.into(),
             }
-            RegistrationError::Identification(e) => {
-                error!("Failed to get GitHub user id. Error: {:?}", e);
-                return Err(HttpApiProblem::new(StatusCode::INTERNAL_SERVER_ERROR)
-                    .title("GitHub GET /user failure")
-                    .detail("Failed to get GitHub user id"));
-            }
-            RegistrationError::Signature(e) => {
-                warn!(
-                    "Signed data has an invalid signature for account {}. Error: {:?}",
-                    registration.account_address, e
-                );
-                return Err(HttpApiProblem::new(StatusCode::UNAUTHORIZED)
-                    .title("Invalid signature")
-                    .detail(format!(
-                        "Signed data has an invalid signature for account {}",
-                        registration.account_address
-                    )));
-            }
-            RegistrationError::Registry(e) => {
-                error!(
-                    "Failed to register account {} in the registry contract. Error: {:?}",
-                    registration.account_address, e
-                );
-                return Err(HttpApiProblem::new(StatusCode::INTERNAL_SERVER_ERROR)
-                    .title("Transaction error")
-                    .detail(format!(
-                        "Failed to register account {} in the registry contract",
-                        registration.account_address
-                    )));
+        }
+        RegistrationOutcome::AlreadyRegistered { transaction_hash } => {
+            info!(
+                "user is already registered with account {}",
+                registration.account_address
+            );
+            UserRegistrationResponse {
+                transaction_hash: transaction_hash.map(Into::into),
+                already_registered: true,
+                profile: profile.into(),
             }
-        },
+        }
     };
 
+    Ok(response)
+}
+
+#[openapi(tag = "Registrations")]
+#[delete("/registrations/github", format = "json", data = "<registration>")]
+pub async fn unregister_github_user(
+    registration: Json<UserRegistrationRequest<'_>>,
+    github_starknet_registerer: &State<Box<GithubStarknetRegisterer>>,
+    badge_registries: &State<BadgeRegistries>,
+) -> Result<Json<GithubUserUnregistrationResponse>, HttpApiProblem> {
+    let transaction_hash = select_registerer(
+        &***github_starknet_registerer,
+        &badge_registries.github,
+        registration.registry,
+    )?
+    .unregister_contributor(
+        parse_authorization(
+            registration.authorization_code,
+            registration.access_token,
+            registration.session,
+            registration.state,
+        )?,
+        registration.account_address.into(),
+        registration.signed_data.clone().into(),
+        parse_nonce(registration.nonce)?,
+    )
+    .await
+    .map_err(|e| {
+        into_problem(
+            e,
+            GITHUB,
+            registration.authorization_code.unwrap_or_default(),
+            registration.account_address,
+            registration.nonce,
+        )
+    })?;
+
     info!(
-        "successfully registered user with account {}",
+        "successfully unregistered user from account {}",
         registration.account_address
     );
-    Ok(Json(GithubUserRegistrationResponse {
+    Ok(Json(GithubUserUnregistrationResponse {
         transaction_hash: transaction_hash.into(),
     }))
 }
 
+#[openapi(tag = "Registrations")]
+#[post(
+    "/registrations/github/migration",
+    format = "json",
+    data = "<migration>"
+)]
+pub async fn migrate_github_user(
+    migration: Json<GithubUserMigrationRequest<'_>>,
+    github_starknet_registerer: &State<Box<GithubStarknetRegisterer>>,
+    badge_registries: &State<BadgeRegistries>,
+) -> Result<Json<GithubUserMigrationResponse>, HttpApiProblem> {
+    let transaction_hash = select_registerer(
+        &***github_starknet_registerer,
+        &badge_registries.github,
+        migration.registry,
+    )?
+    .migrate_contributor(
+        parse_authorization(
+            migration.authorization_code,
+            migration.access_token,
+            migration.session,
+            migration.state,
+        )?,
+        migration.old_account_address.into(),
+        migration.account_address.into(),
+        migration.signed_data.clone().into(),
+        migration.old_signed_data.clone().map(Into::into),
+        parse_nonce(migration.nonce)?,
+    )
+    .await
+    .map_err(|e| match e {
+        RegistrationError::NotRegistered => {
+            warn!(
+                "Identity is not registered with account {}",
+                migration.old_account_address
+            );
+            HttpApiProblem::new(StatusCode::NOT_FOUND)
+                .title("Not registered")
+                .detail(format!(
+                    "Identity is not registered with account {}",
+                    migration.old_account_address
+                ))
+        }
+        e => into_problem(
+            e,
+            GITHUB,
+            migration.authorization_code.unwrap_or_default(),
+            migration.account_address,
+            migration.nonce,
+        ),
+    })?;
+
+    info!(
+        "successfully migrated user from account {} to account {}",
+        migration.old_account_address, migration.account_address
+    );
+    Ok(Json(GithubUserMigrationResponse {
+        transaction_hash: transaction_hash.into(),
+    }))
+}
+
+fn parse_authorization(
+    authorization_code: Option<&str>,
+    access_token: Option<&str>,
+    session: Option<&str>,
+    state: Option<&str>,
+) -> Result<Authorization, HttpApiProblem> {
+    match (authorization_code, access_token, session) {
+        (Some(authorization_code), None, None) => Ok(Authorization::Code {
+            code: authorization_code.to_string(),
+            state: state.map(ToString::to_string),
+        }),
+        (None, Some(access_token), None) => Ok(Authorization::AccessToken(AccessToken::from(
+            access_token.to_string(),
+        ))),
+        (None, None, Some(session)) => Ok(Authorization::Session(session.to_string())),
+        _ => Err(HttpApiProblem::new(StatusCode::BAD_REQUEST)
+            .title("Invalid authorization")
+            .detail("Exactly one of authorization_code, access_token and session must be given")),
+    }
+}
+
+fn parse_nonce(nonce: &str) -> Result<Nonce, HttpApiProblem> {
+    nonce.parse().map_err(|_| {
+        HttpApiProblem::new(StatusCode::BAD_REQUEST)
+            .title("Invalid nonce")
+            .detail(format!("Nonce {} is not a valid hexadecimal value", nonce))
+    })
+}
+
+fn into_problem(
+    error: RegistrationError,
+    provider: &str,
+    authorization_code: &str,
+    account_address: HexFieldElement,
+    nonce: &str,
+) -> HttpApiProblem {
+    match error {
+        RegistrationError::Authentication(e) => {
+            warn!(
+                "Failed to get new {} access token from code {}. Error: {:?}",
+                provider, authorization_code, e
+            );
+            HttpApiProblem::new(StatusCode::UNAUTHORIZED)
+                .title(format!("Invalid {} code", provider))
+                .detail(format!(
+                    "Failed to get new {} access token from code {}",
+                    provider, authorization_code
+                ))
+        }
+        RegistrationError::Identification(IdentificationError::Unauthorized) => {
+            warn!("{} access token was rejected", provider);
+            HttpApiProblem::new(StatusCode::UNAUTHORIZED)
+                .title(format!("Invalid {} access token", provider))
+                .detail(format!(
+                    "{} access token is invalid, expired or revoked",
+                    provider
+                ))
+        }
+        RegistrationError::Identification(e) => {
+            error!("Failed to get {} user id. Error: {:?}", provider, e);
+            HttpApiProblem::new(StatusCode::INTERNAL_SERVER_ERROR)
+                .title(format!("{} GET /user failure", provider))
+                .detail(format!("Failed to get {} user id", provider))
+        }
+        RegistrationError::State(e) => {
+            warn!("Invalid {} OAuth state. Error: {:?}", provider, e);
+            HttpApiProblem::new(StatusCode::UNAUTHORIZED)
+                .title("Invalid state")
+                .detail(format!(
+                    "{}, authorization requests must start from GET /oauth/{}/authorize",
+                    e,
+                    provider.to_lowercase()
+                ))
+        }
+        RegistrationError::Session(IdentitySessionError::Unsupported) => {
+            disabled_session_problem(provider)
+        }
+        RegistrationError::Session(e) => {
+            warn!("Invalid {} identity session. Error: {:?}", provider, e);
+            HttpApiProblem::new(StatusCode::UNAUTHORIZED)
+                .title("Invalid session")
+                .detail(format!("{}", e))
+        }
+        RegistrationError::Signature(SignatureError::AccountNotDeployed) => {
+            warn!("Signing account {} is not deployed", account_address);
+            HttpApiProblem::new(StatusCode::UNPROCESSABLE_ENTITY)
+                .title("Account not deployed")
+                .detail(format!(
+                    "Account {} must be deployed before signing up",
+                    account_address
+                ))
+        }
+        RegistrationError::Signature(SignatureError::UnsupportedAccount(class_hash)) => {
+            warn!(
+                "Signing account {} has unsupported class {}",
+                account_address, class_hash
+            );
+            HttpApiProblem::new(StatusCode::UNPROCESSABLE_ENTITY)
+                .title("Unsupported account")
+                .detail(format!(
+                    "Account {} is not a supported account implementation",
+                    account_address
+                ))
+        }
+        RegistrationError::Signature(SignatureError::Provider(e)) => {
+            error!(
+                "Failed to verify the signature of account {}. Error: {:?}",
+                account_address, e
+            );
+            HttpApiProblem::new(StatusCode::INTERNAL_SERVER_ERROR)
+                .title("Signature verification failure")
+                .detail(format!(
+                    "Failed to verify the signature of account {}",
+                    account_address
+                ))
+        }
+        RegistrationError::Signature(e) => {
+            warn!(
+                "Signed data has an invalid signature for account {}. Error: {:?}",
+                account_address, e
+            );
+            HttpApiProblem::new(StatusCode::UNAUTHORIZED)
+                .title("Invalid signature")
+                .detail(format!(
+                    "Signed data has an invalid signature for account {}",
+                    account_address
+                ))
+        }
+        RegistrationError::Challenge(e) => {
+            warn!(
+                "Invalid registration challenge nonce {}. Error: {:?}",
+                nonce, e
+            );
+            HttpApiProblem::new(StatusCode::UNAUTHORIZED)
+                .title("Invalid nonce")
+                .detail(format!(
+                    "Nonce {} is unknown, expired, already used or issued for another account",
+                    nonce
+                ))
+        }
+        RegistrationError::Registry(e) => {
+            error!(
+                "Failed to send transaction for account {} to the registry contract. Error: {:?}",
+                account_address, e
+            );
+            HttpApiProblem::new(StatusCode::INTERNAL_SERVER_ERROR)
+                .title("Transaction error")
+                .detail(format!(
+                    "Failed to send transaction for account {} to the registry contract",
+                    account_address
+                ))
+        }
+        RegistrationError::AlreadyRegistered => {
+            warn!(
+                "Identity is already registered with another account than {}",
+                account_address
+            );
+            HttpApiProblem::new(StatusCode::CONFLICT)
+                .title("Already registered")
+                .detail(format!(
+                    "Identity is already registered with another account than {}",
+                    account_address
+                ))
+        }
+        RegistrationError::NotRegistered => {
+            warn!(
+                "Identity is not registered with account {}",
+                account_address
+            );
+            HttpApiProblem::new(StatusCode::NOT_FOUND)
+                .title("Not registered")
+                .detail(format!(
+                    "Identity is not registered with account {}",
+                    account_address
+                ))
+        }
+        error @ (RegistrationError::AccountTooRecent
+        | RegistrationError::NotEnoughRepositories
+        | RegistrationError::NotEnoughFollowers
+        | RegistrationError::AccountSuspended
+        | RegistrationError::AccountDenied
+        | RegistrationError::NotAMember) => {
+            warn!(
+                "{} account is not eligible to register with account {}. Error: {:?}",
+                provider, account_address, error
+            );
+            HttpApiProblem::new(StatusCode::FORBIDDEN)
+                .title("Account not eligible")
+                .detail(format!("{}", error))
+                .value("reason", &error.kind())
+        }
+        RegistrationError::Repository(e) => {
+            error!("Failed to record registration attempt. Error: {:?}", e);
+            HttpApiProblem::new(StatusCode::INTERNAL_SERVER_ERROR)
+                .title("Storage error")
+                .detail("Failed to record registration attempt")
+        }
+        RegistrationError::UnknownTransaction => {
+            error!("Registration transaction is unknown to the network");
+            HttpApiProblem::new(StatusCode::INTERNAL_SERVER_ERROR)
+                .title("Transaction error")
+                .detail("Registration transaction is unknown to the network")
+        }
+    }
+}
+
+#[openapi(tag = "Registrations")]
+#[get("/registrations/<transaction_hash>")]
+pub async fn get_registration_status(
+    transaction_hash: HexFieldElement,
+    github_starknet_registerer: &State<Box<GithubStarknetRegisterer>>,
+) -> Result<Json<RegistrationStatusResponse>, HttpApiProblem> {
+    let status = github_starknet_registerer
+        .get_registration_status(transaction_hash.into())
+        .await
+        .map_err(|e| match e {
+            RegistrationError::UnknownTransaction => HttpApiProblem::new(StatusCode::NOT_FOUND)
+                .title("Unknown transaction")
+                .detail(format!(
+                    "Transaction {} is neither known to the network nor sent by this service",
+                    transaction_hash
+                )),
+            e => {
+                error!(
+                    "Failed to get status of transaction {}. Error: {:?}",
+                    transaction_hash, e
+                );
+                HttpApiProblem::new(StatusCode::INTERNAL_SERVER_ERROR)
+                    .title("Transaction status error")
+                    .detail(format!(
+                        "Failed to get status of transaction {}",
+                        transaction_hash
+                    ))
+            }
+        })?;
+
+    Ok(Json(RegistrationStatusResponse::new(
+        transaction_hash,
+        status,
+    )))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::infrastructure::StarknetSignature;
     use crate::infrastructure::StarknetSignedData;
+    use std::time::{Duration, UNIX_EPOCH};
+
     use crate::{
         application::registerer::Registerer,
-        domain::{errors::RegistrationError, services::onchain_registry::OnChainRegistry},
-        infrastructure::{github_client::GitHubClient, starknet_client::StarkNetClient},
-        rest::{self},
+        domain::{
+            errors::{AuthorizationStateError, RegistrationError, SignatureError},
+            services::onchain_registry::OnChainRegistry,
+            value_objects::{
+                AccessToken, Authorization, Challenge, IdentityProfile, IdentitySession, Nonce,
+                RegistrationOutcome, TransactionStatus,
+            },
+        },
+        infrastructure::{
+            github_client::GitHubClient, in_memory_idempotency_store::InMemoryIdempotencyStore,
+            starknet_client::StarkNetClient,
+        },
+        rest::{self, registrations::BadgeRegistries},
     };
     use claim::assert_some_eq;
-    use mockall::{mock, predicate::eq};
+    use mockall::{
+        mock,
+        predicate::{always, eq},
+    };
     use rocket::{
-        http::{ContentType, Status},
+        http::{ContentType, Header, Status},
         local::blocking::Client,
-        serde::json::serde_json::json,
+        serde::json::serde_json::{self, json},
     };
     use starknet::macros::felt;
 
@@ -107,12 +699,45 @@ mod tests {
         MyRegisterer {}
         #[async_trait]
         impl Registerer<GitHubClient, StarkNetClient> for MyRegisterer {
+            async fn new_challenge(&self, account_address: <StarkNetClient as OnChainRegistry>::AccountAddress) -> Challenge;
+
+            async fn new_authorization_url(&self) -> Option<String>;
+
+            async fn open_session(
+                &self,
+                authorization: Authorization,
+            ) -> Result<IdentitySession, RegistrationError>;
+
             async fn register_contributor(
                 &self,
-                authorization_code: String,
+                authorization: Authorization,
+                account_address: <StarkNetClient as OnChainRegistry>::AccountAddress,
+                signed_data: <StarkNetClient as OnChainRegistry>::SignedData,
+                nonce: Nonce,
+            ) -> Result<(RegistrationOutcome<<StarkNetClient as OnChainRegistry>::TransactionHash>, IdentityProfile), RegistrationError>;
+
+            async fn unregister_contributor(
+                &self,
+                authorization: Authorization,
                 account_address: <StarkNetClient as OnChainRegistry>::AccountAddress,
                 signed_data: <StarkNetClient as OnChainRegistry>::SignedData,
+                nonce: Nonce,
+            ) -> Result<<StarkNetClient as OnChainRegistry>::TransactionHash, RegistrationError>;
+
+            async fn migrate_contributor(
+                &self,
+                authorization: Authorization,
+                old_account_address: <StarkNetClient as OnChainRegistry>::AccountAddress,
+                new_account_address: <StarkNetClient as OnChainRegistry>::AccountAddress,
+                new_signed_data: <StarkNetClient as OnChainRegistry>::SignedData,
+                old_signed_data: Option<<StarkNetClient as OnChainRegistry>::SignedData>,
+                nonce: Nonce,
             ) -> Result<<StarkNetClient as OnChainRegistry>::TransactionHash, RegistrationError>;
+
+            async fn get_registration_status(
+                &self,
+                transaction_hash: <StarkNetClient as OnChainRegistry>::TransactionHash,
+            ) -> Result<TransactionStatus, RegistrationError>;
         }
     }
 
@@ -123,7 +748,10 @@ mod tests {
         registerer_mock
             .expect_register_contributor()
             .with(
-                eq("foo-code".to_string()),
+                eq(Authorization::Code {
+                    code: "foo-code".to_string(),
+                    state: None,
+                }),
                 eq(felt!(
                     "0x65f1506b7f974a1355aeebc1314579326c84a029cd8257a91f82384a6a0ace"
                 )),
@@ -131,21 +759,24 @@ mod tests {
                     hash: felt!(
                         "0x287b943b1934949486006ad63ac0293038b6c818b858b09f8e0a9da12fc4074"
                     ),
-                    signature: StarknetSignature {
-                        r: felt!(
-                            "0xde4d49b21dd8714eaf5a1b480d8ede84d2230d1763cfe06762d8a117493bcd"
-                        ),
-                        s: felt!(
-                            "0x4b61402b98b29a34bd4cba8b5eabae840809914160002385444059f59449a4"
-                        ),
-                    },
+                    signature: StarknetSignature(vec![
+                        felt!("0xde4d49b21dd8714eaf5a1b480d8ede84d2230d1763cfe06762d8a117493bcd"),
+                        felt!("0x4b61402b98b29a34bd4cba8b5eabae840809914160002385444059f59449a4"),
+                    ]),
                 }),
+                eq(Nonce::from(0x2a)),
             )
             .times(1)
-            .returning(|_, _, _| Ok(felt!("0x666")));
+            .returning(|_, _, _, _| {
+                Ok((
+                    RegistrationOutcome::Submitted(felt!("0x666")),
+                    octocat_profile(),
+                ))
+            });
 
         let router = rest::router::new(
-            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
         );
 
         let client = Client::tracked(router).expect("valid rocket instance");
@@ -163,6 +794,7 @@ mod tests {
                             "s": "0x4b61402b98b29a34bd4cba8b5eabae840809914160002385444059f59449a4"
                         }
                     },
+                    "nonce": "0x2a",
                 })
                 .to_string(),
             )
@@ -170,6 +802,853 @@ mod tests {
 
         assert_eq!(response.status(), Status::Ok);
         let body = response.into_string();
-        assert_some_eq!(body, "{\"transaction_hash\":\"0x666\"}".to_string());
+        assert_some_eq!(
+            body,
+            format!(
+                "{{\"transaction_hash\":\"0x666\",\"profile\":{}}}",
+                OCTOCAT_PROFILE
+            )
+        );
+    }
+
+    const OCTOCAT_PROFILE: &str =
+        "{\"login\":\"octocat\",\"avatar_url\":\"https://avatars.githubusercontent.com/u/42\"}";
+
+    fn octocat_profile() -> IdentityProfile {
+        IdentityProfile {
+            login: "octocat".to_string(),
+            name: None,
+            avatar_url: Some("https://avatars.githubusercontent.com/u/42".to_string()),
+            created_at: None,
+            public_repos: None,
+            followers: None,
+            suspended: false,
+        }
+    }
+
+    fn registration_body() -> String {
+        json!({
+            "authorization_code": "foo-code",
+            "account_address": "0x65f1506b7f974a1355aeebc1314579326c84a029cd8257a91f82384a6a0ace",
+            "signed_data": {
+                "hash": "0x287b943b1934949486006ad63ac0293038b6c818b858b09f8e0a9da12fc4074",
+                "signature": {
+                    "r": "0xde4d49b21dd8714eaf5a1b480d8ede84d2230d1763cfe06762d8a117493bcd",
+                    "s": "0x4b61402b98b29a34bd4cba8b5eabae840809914160002385444059f59449a4"
+                }
+            },
+            "nonce": "0x2a",
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_register_github_user_with_access_token() {
+        let mut registerer_mock = MockMyRegisterer::new();
+
+        registerer_mock
+            .expect_register_contributor()
+            .with(
+                eq(Authorization::AccessToken(AccessToken::from(
+                    "ghu_token".to_string(),
+                ))),
+                always(),
+                always(),
+                always(),
+            )
+            .times(1)
+            .returning(|_, _, _, _| {
+                Ok((
+                    RegistrationOutcome::Submitted(felt!("0x666")),
+                    octocat_profile(),
+                ))
+            });
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let mut body: serde_json::Value = serde_json::from_str(&registration_body()).unwrap();
+        body["authorization_code"].take();
+        body["access_token"] = json!("ghu_token");
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .post(uri!("/registrations/github"))
+            .header(ContentType::JSON)
+            .body(body.to_string())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn test_register_github_user_with_signature_array() {
+        let mut registerer_mock = MockMyRegisterer::new();
+
+        registerer_mock
+            .expect_register_contributor()
+            .with(
+                always(),
+                always(),
+                eq(StarknetSignedData {
+                    hash: felt!(
+                        "0x287b943b1934949486006ad63ac0293038b6c818b858b09f8e0a9da12fc4074"
+                    ),
+                    signature: StarknetSignature(vec![
+                        felt!("0x1"),
+                        felt!("0x2"),
+                        felt!("0x3"),
+                        felt!("0x4"),
+                    ]),
+                }),
+                always(),
+            )
+            .times(1)
+            .returning(|_, _, _, _| {
+                Ok((
+                    RegistrationOutcome::Submitted(felt!("0x666")),
+                    octocat_profile(),
+                ))
+            });
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let mut body: serde_json::Value = serde_json::from_str(&registration_body()).unwrap();
+        body["signed_data"]["signature"] = json!(["0x1", "0x2", "0x3", "0x4"]);
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .post(uri!("/registrations/github"))
+            .header(ContentType::JSON)
+            .body(body.to_string())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn test_register_github_user_with_session() {
+        let mut registerer_mock = MockMyRegisterer::new();
+
+        registerer_mock
+            .expect_register_contributor()
+            .with(
+                eq(Authorization::Session("foo-session".to_string())),
+                always(),
+                always(),
+                always(),
+            )
+            .times(1)
+            .returning(|_, _, _, _| {
+                Ok((
+                    RegistrationOutcome::Submitted(felt!("0x666")),
+                    octocat_profile(),
+                ))
+            });
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let mut body: serde_json::Value = serde_json::from_str(&registration_body()).unwrap();
+        body["authorization_code"].take();
+        body["session"] = json!("foo-session");
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .post(uri!("/registrations/github"))
+            .header(ContentType::JSON)
+            .body(body.to_string())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+    }
+
+    #[test]
+    fn test_register_github_user_with_code_and_access_token() {
+        let mut registerer_mock = MockMyRegisterer::new();
+        registerer_mock.expect_register_contributor().never();
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let mut body: serde_json::Value = serde_json::from_str(&registration_body()).unwrap();
+        body["access_token"] = json!("ghu_token");
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .post(uri!("/registrations/github"))
+            .header(ContentType::JSON)
+            .body(body.to_string())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_register_github_user_with_unknown_state() {
+        let mut registerer_mock = MockMyRegisterer::new();
+        registerer_mock
+            .expect_register_contributor()
+            .with(
+                eq(Authorization::Code {
+                    code: "foo-code".to_string(),
+                    state: Some("foo-state".to_string()),
+                }),
+                always(),
+                always(),
+                always(),
+            )
+            .times(1)
+            .returning(|_, _, _, _| {
+                Err(RegistrationError::State(AuthorizationStateError::Unknown))
+            });
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let mut body: serde_json::Value = serde_json::from_str(&registration_body()).unwrap();
+        body["state"] = json!("foo-state");
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .post(uri!("/registrations/github"))
+            .header(ContentType::JSON)
+            .body(body.to_string())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Unauthorized);
+    }
+
+    #[test]
+    fn test_register_already_registered_github_user() {
+        let mut registerer_mock = MockMyRegisterer::new();
+
+        registerer_mock
+            .expect_register_contributor()
+            .times(1)
+            .returning(|_, _, _, _| {
+                Ok((
+                    RegistrationOutcome::AlreadyRegistered {
+                        transaction_hash: Some(felt!("0x666")),
+                    },
+                    octocat_profile(),
+                ))
+            });
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .post(uri!("/registrations/github"))
+            .header(ContentType::JSON)
+            .body(registration_body())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let body = response.into_string();
+        assert_some_eq!(
+            body,
+            format!(
+                "{{\"transaction_hash\":\"0x666\",\"already_registered\":true,\"profile\":{}}}",
+                OCTOCAT_PROFILE
+            )
+        );
+    }
+
+    #[test]
+    fn test_register_github_user_with_idempotency_key() {
+        let mut registerer_mock = MockMyRegisterer::new();
+
+        registerer_mock
+            .expect_register_contributor()
+            .times(1)
+            .returning(|_, _, _, _| {
+                Ok((
+                    RegistrationOutcome::Submitted(felt!("0x666")),
+                    octocat_profile(),
+                ))
+            });
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        for _ in 0..2 {
+            let response = client
+                .post(uri!("/registrations/github"))
+                .header(ContentType::JSON)
+                .header(Header::new("Idempotency-Key", "foo-key"))
+                .body(registration_body())
+                .dispatch();
+
+            assert_eq!(response.status(), Status::Ok);
+            let body = response.into_string();
+            assert_some_eq!(
+                body,
+                format!(
+                    "{{\"transaction_hash\":\"0x666\",\"profile\":{}}}",
+                    OCTOCAT_PROFILE
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn test_register_github_user_failure_with_idempotency_key() {
+        let mut registerer_mock = MockMyRegisterer::new();
+
+        registerer_mock
+            .expect_register_contributor()
+            .times(1)
+            .returning(|_, _, _, _| Err(RegistrationError::AlreadyRegistered));
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        for _ in 0..2 {
+            let response = client
+                .post(uri!("/registrations/github"))
+                .header(ContentType::JSON)
+                .header(Header::new("Idempotency-Key", "foo-key"))
+                .body(registration_body())
+                .dispatch();
+
+            assert_eq!(response.status(), Status::Conflict);
+        }
+    }
+
+    #[test]
+    fn test_reuse_idempotency_key_for_another_registration() {
+        let mut registerer_mock = MockMyRegisterer::new();
+
+        registerer_mock
+            .expect_register_contributor()
+            .times(1)
+            .returning(|_, _, _, _| {
+                Ok((
+                    RegistrationOutcome::Submitted(felt!("0x666")),
+                    octocat_profile(),
+                ))
+            });
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .post(uri!("/registrations/github"))
+            .header(ContentType::JSON)
+            .header(Header::new("Idempotency-Key", "foo-key"))
+            .body(registration_body())
+            .dispatch();
+        assert_eq!(response.status(), Status::Ok);
+
+        let mut body: serde_json::Value = serde_json::from_str(&registration_body()).unwrap();
+        body["account_address"] = json!("0x777");
+        let response = client
+            .post(uri!("/registrations/github"))
+            .header(ContentType::JSON)
+            .header(Header::new("Idempotency-Key", "foo-key"))
+            .body(body.to_string())
+            .dispatch();
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+    }
+
+    #[test]
+    fn test_register_github_user_registered_with_another_account() {
+        let mut registerer_mock = MockMyRegisterer::new();
+
+        registerer_mock
+            .expect_register_contributor()
+            .times(1)
+            .returning(|_, _, _, _| Err(RegistrationError::AlreadyRegistered));
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .post(uri!("/registrations/github"))
+            .header(ContentType::JSON)
+            .body(registration_body())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Conflict);
+    }
+
+    #[test]
+    fn test_register_ineligible_github_user() {
+        let mut registerer_mock = MockMyRegisterer::new();
+
+        registerer_mock
+            .expect_register_contributor()
+            .times(1)
+            .returning(|_, _, _, _| Err(RegistrationError::AccountTooRecent));
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .post(uri!("/registrations/github"))
+            .header(ContentType::JSON)
+            .body(registration_body())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Forbidden);
+        let body = response.into_string().unwrap();
+        assert!(
+            body.contains("\"reason\":\"account_too_recent\""),
+            "{}",
+            body
+        );
+    }
+
+    #[test]
+    fn test_register_github_user_with_undeployed_account() {
+        let mut registerer_mock = MockMyRegisterer::new();
+
+        registerer_mock
+            .expect_register_contributor()
+            .times(1)
+            .returning(|_, _, _, _| {
+                Err(RegistrationError::Signature(
+                    SignatureError::AccountNotDeployed,
+                ))
+            });
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .post(uri!("/registrations/github"))
+            .header(ContentType::JSON)
+            .body(registration_body())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+    }
+
+    #[test]
+    fn test_register_github_user_with_unsupported_account() {
+        let mut registerer_mock = MockMyRegisterer::new();
+
+        registerer_mock
+            .expect_register_contributor()
+            .times(1)
+            .returning(|_, _, _, _| {
+                Err(RegistrationError::Signature(
+                    SignatureError::UnsupportedAccount("0x2a".to_string()),
+                ))
+            });
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .post(uri!("/registrations/github"))
+            .header(ContentType::JSON)
+            .body(registration_body())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::UnprocessableEntity);
+    }
+
+    #[test]
+    fn test_unregister_github_user() {
+        let mut registerer_mock = MockMyRegisterer::new();
+
+        registerer_mock
+            .expect_unregister_contributor()
+            .with(
+                eq(Authorization::Code {
+                    code: "foo-code".to_string(),
+                    state: None,
+                }),
+                eq(felt!(
+                    "0x65f1506b7f974a1355aeebc1314579326c84a029cd8257a91f82384a6a0ace"
+                )),
+                always(),
+                eq(Nonce::from(0x2a)),
+            )
+            .times(1)
+            .returning(|_, _, _, _| Ok(felt!("0x777")));
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .delete(uri!("/registrations/github"))
+            .header(ContentType::JSON)
+            .body(registration_body())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let body = response.into_string();
+        assert_some_eq!(body, "{\"transaction_hash\":\"0x777\"}".to_string());
+    }
+
+    #[test]
+    fn test_unregister_not_registered_github_user() {
+        let mut registerer_mock = MockMyRegisterer::new();
+
+        registerer_mock
+            .expect_unregister_contributor()
+            .times(1)
+            .returning(|_, _, _, _| Err(RegistrationError::NotRegistered));
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .delete(uri!("/registrations/github"))
+            .header(ContentType::JSON)
+            .body(registration_body())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_migrate_github_user() {
+        let mut registerer_mock = MockMyRegisterer::new();
+
+        registerer_mock
+            .expect_migrate_contributor()
+            .with(
+                eq(Authorization::Code {
+                    code: "foo-code".to_string(),
+                    state: None,
+                }),
+                eq(felt!("0x65f1")),
+                eq(felt!("0x65f2")),
+                always(),
+                eq(None),
+                eq(Nonce::from(0x2a)),
+            )
+            .times(1)
+            .returning(|_, _, _, _, _, _| Ok(felt!("0x888")));
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .post(uri!("/registrations/github/migration"))
+            .header(ContentType::JSON)
+            .body(
+                json!({
+                    "authorization_code": "foo-code",
+                    "old_account_address": "0x65f1",
+                    "account_address": "0x65f2",
+                    "signed_data": {
+                        "hash": "0x1",
+                        "signature": {
+                            "r": "0x2",
+                            "s": "0x3"
+                        }
+                    },
+                    "nonce": "0x2a",
+                })
+                .to_string(),
+            )
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let body = response.into_string();
+        assert_some_eq!(body, "{\"transaction_hash\":\"0x888\"}".to_string());
+    }
+
+    #[test]
+    fn test_register_user_with_disabled_identity_provider() {
+        let router = rest::router::new(
+            Box::new(MockMyRegisterer::new()) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .post(uri!("/registrations/gitlab"))
+            .header(ContentType::JSON)
+            .body(registration_body())
+            .dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    fn registration_body_for_registry(registry: &str) -> String {
+        let mut body: serde_json::Value = serde_json::from_str(&registration_body()).unwrap();
+        body["registry"] = json!(registry);
+        body.to_string()
+    }
+
+    #[test]
+    fn test_register_github_user_with_registry() {
+        let mut default_registerer_mock = MockMyRegisterer::new();
+        default_registerer_mock
+            .expect_register_contributor()
+            .never();
+
+        let mut registerer_mock = MockMyRegisterer::new();
+        registerer_mock
+            .expect_register_contributor()
+            .times(1)
+            .returning(|_, _, _, _| {
+                Ok((
+                    RegistrationOutcome::Submitted(felt!("0x777")),
+                    octocat_profile(),
+                ))
+            });
+
+        let mut badge_registries = BadgeRegistries::default();
+        badge_registries.github.insert(
+            "hackathon".to_string(),
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+        );
+        let router = rest::router::new_with_registries(
+            Box::new(default_registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            None,
+            None,
+            None,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+            badge_registries,
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .post(uri!("/registrations/github"))
+            .header(ContentType::JSON)
+            .body(registration_body_for_registry("hackathon"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let body = response.into_string();
+        assert_some_eq!(
+            body,
+            format!(
+                "{{\"transaction_hash\":\"0x777\",\"profile\":{}}}",
+                OCTOCAT_PROFILE
+            )
+        );
+    }
+
+    #[test]
+    fn test_register_github_user_with_unknown_registry() {
+        let mut registerer_mock = MockMyRegisterer::new();
+        registerer_mock.expect_register_contributor().never();
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .post(uri!("/registrations/github"))
+            .header(ContentType::JSON)
+            .body(registration_body_for_registry("hackathon"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_new_registration_challenge() {
+        let mut registerer_mock = MockMyRegisterer::new();
+
+        registerer_mock
+            .expect_new_challenge()
+            .with(eq(felt!("0x65f1")))
+            .times(1)
+            .returning(|_| Challenge {
+                nonce: Nonce::from(0x2a),
+                expires_at: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            });
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .get(uri!("/registrations/challenge?account_address=0x65f1"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let body = response.into_string();
+        assert_some_eq!(
+            body,
+            "{\"nonce\":\"0x2a\",\"expires_at\":1700000000}".to_string()
+        );
+    }
+
+    #[test]
+    fn test_new_registration_challenge_for_invalid_account() {
+        let mut registerer_mock = MockMyRegisterer::new();
+        registerer_mock.expect_new_challenge().never();
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .get(uri!("/registrations/challenge?account_address=foo"))
+            .dispatch();
+
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_register_github_user_with_invalid_nonce() {
+        let mut registerer_mock = MockMyRegisterer::new();
+        registerer_mock.expect_register_contributor().never();
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client
+            .post(uri!("/registrations/github"))
+            .header(ContentType::JSON)
+            .body(
+                json!({
+                    "authorization_code": "foo-code",
+                    "account_address": "0x65f1506b7f974a1355aeebc1314579326c84a029cd8257a91f82384a6a0ace",
+                    "signed_data": {
+                        "hash": "0x287b943b1934949486006ad63ac0293038b6c818b858b09f8e0a9da12fc4074",
+                        "signature": {
+                            "r": "0xde4d49b21dd8714eaf5a1b480d8ede84d2230d1763cfe06762d8a117493bcd",
+                            "s": "0x4b61402b98b29a34bd4cba8b5eabae840809914160002385444059f59449a4"
+                        }
+                    },
+                    "nonce": "not-a-nonce",
+                })
+                .to_string(),
+            )
+            .dispatch();
+
+        assert_eq!(response.status(), Status::BadRequest);
+    }
+
+    #[test]
+    fn test_get_registration_status() {
+        let mut registerer_mock = MockMyRegisterer::new();
+
+        registerer_mock
+            .expect_get_registration_status()
+            .with(eq(felt!("0x666")))
+            .times(1)
+            .returning(|_| {
+                Ok(TransactionStatus::Rejected {
+                    reason: Some("out of gas".to_string()),
+                })
+            });
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client.get(uri!("/registrations/0x666")).dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let body = response.into_string();
+        assert_some_eq!(
+            body,
+            "{\"transaction_hash\":\"0x666\",\"status\":\"rejected\",\"failure_reason\":\"out of gas\"}"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_get_unknown_registration_status() {
+        let mut registerer_mock = MockMyRegisterer::new();
+
+        registerer_mock
+            .expect_get_registration_status()
+            .with(eq(felt!("0x666")))
+            .times(1)
+            .returning(|_| Err(RegistrationError::UnknownTransaction));
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client.get(uri!("/registrations/0x666")).dispatch();
+
+        assert_eq!(response.status(), Status::NotFound);
+    }
+
+    #[test]
+    fn test_get_pending_registration_status() {
+        let mut registerer_mock = MockMyRegisterer::new();
+
+        registerer_mock
+            .expect_get_registration_status()
+            .with(eq(felt!("0x666")))
+            .times(1)
+            .returning(|_| Ok(TransactionStatus::Pending));
+
+        let router = rest::router::new(
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
+        );
+
+        let client = Client::tracked(router).expect("valid rocket instance");
+        let response = client.get(uri!("/registrations/0x666")).dispatch();
+
+        assert_eq!(response.status(), Status::Ok);
+        let body = response.into_string();
+        assert_some_eq!(
+            body,
+            "{\"transaction_hash\":\"0x666\",\"status\":\"pending\"}".to_string()
+        );
     }
 }
@@ -6,12 +6,58 @@ use rocket_okapi::{
 
 use crate::{
     application::registerer::Registerer,
-    infrastructure::{github_client::GitHubClient, starknet_client::StarkNetClient},
+    domain::services::device_authorization_provider::DeviceAuthorizationProvider,
+    infrastructure::{
+        discord_client::DiscordClient, github_client::GitHubClient, gitlab_client::GitLabClient,
+        starknet_client::StarkNetClient,
+    },
 };
 
-pub fn new(registerer: Box<dyn Registerer<GitHubClient, StarkNetClient>>) -> Rocket<Build> {
+use super::registrations::{BadgeRegistries, RegistrationIdempotencyStore};
+
+pub fn new(
+    registerer: Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+    idempotency_store: Box<RegistrationIdempotencyStore>,
+) -> Rocket<Build> {
+    new_with_providers(registerer, None, None, None, idempotency_store)
+}
+
+/// Builds the router with the optional identity providers and GitHub device authorization flow.
+/// Requests to a disabled provider or flow are answered with a 404 problem.
+pub fn new_with_providers(
+    registerer: Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+    gitlab_registerer: Option<Box<dyn Registerer<GitLabClient, StarkNetClient>>>,
+    discord_registerer: Option<Box<dyn Registerer<DiscordClient, StarkNetClient>>>,
+    device_authorization_provider: Option<Box<dyn DeviceAuthorizationProvider>>,
+    idempotency_store: Box<RegistrationIdempotencyStore>,
+) -> Rocket<Build> {
+    new_with_registries(
+        registerer,
+        gitlab_registerer,
+        discord_registerer,
+        device_authorization_provider,
+        idempotency_store,
+        BadgeRegistries::default(),
+    )
+}
+
+/// Builds the router serving the default badge registry, along with the `badge_registries`
+/// selected by the `registry` field of requests.
+pub fn new_with_registries(
+    registerer: Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+    gitlab_registerer: Option<Box<dyn Registerer<GitLabClient, StarkNetClient>>>,
+    discord_registerer: Option<Box<dyn Registerer<DiscordClient, StarkNetClient>>>,
+    device_authorization_provider: Option<Box<dyn DeviceAuthorizationProvider>>,
+    idempotency_store: Box<RegistrationIdempotencyStore>,
+    badge_registries: BadgeRegistries,
+) -> Rocket<Build> {
     rocket::build()
         .manage(registerer)
+        .manage(gitlab_registerer)
+        .manage(discord_registerer)
+        .manage(device_authorization_provider)
+        .manage(idempotency_store)
+        .manage(badge_registries)
         .attach(super::cors::Cors)
         .mount(
             "/",
@@ -22,7 +68,20 @@ pub fn new(registerer: Box<dyn Registerer<GitHubClient, StarkNetClient>>) -> Roc
         )
         .mount(
             "/",
-            openapi_get_routes![super::registrations::register_github_user],
+            openapi_get_routes![
+                super::registrations::new_registration_challenge,
+                super::registrations::register_github_user,
+                super::registrations::register_gitlab_user,
+                super::registrations::register_discord_user,
+                super::registrations::unregister_github_user,
+                super::registrations::migrate_github_user,
+                super::registrations::get_registration_status,
+                super::oauth::authorize_github_user,
+                super::oauth::github_callback,
+                super::oauth::start_github_device_authorization,
+                super::oauth::poll_github_device_authorization,
+                super::oauth::refresh_github_access_token
+            ],
         )
         .mount("/swagger", make_swagger_ui(&get_docs()))
 }
@@ -36,13 +95,25 @@ pub(crate) fn get_docs() -> SwaggerUIConfig {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use mockall::mock;
     use rocket::{http::Status, local::blocking::Client};
 
     use crate::{
         application::registerer::Registerer,
-        domain::{errors::RegistrationError, services::onchain_registry::OnChainRegistry},
-        infrastructure::{github_client::GitHubClient, starknet_client::StarkNetClient},
+        domain::{
+            errors::RegistrationError,
+            services::onchain_registry::OnChainRegistry,
+            value_objects::{
+                Authorization, Challenge, IdentityProfile, IdentitySession, Nonce,
+                RegistrationOutcome, TransactionStatus,
+            },
+        },
+        infrastructure::{
+            github_client::GitHubClient, in_memory_idempotency_store::InMemoryIdempotencyStore,
+            starknet_client::StarkNetClient,
+        },
         rest,
     };
 
@@ -50,12 +121,45 @@ mod tests {
         MyRegisterer {}
         #[async_trait]
         impl Registerer<GitHubClient, StarkNetClient> for MyRegisterer {
+            async fn new_challenge(&self, account_address: <StarkNetClient as OnChainRegistry>::AccountAddress) -> Challenge;
+
+            async fn new_authorization_url(&self) -> Option<String>;
+
+            async fn open_session(
+                &self,
+                authorization: Authorization,
+            ) -> Result<IdentitySession, RegistrationError>;
+
             async fn register_contributor(
                 &self,
-                authorization_code: String,
+                authorization: Authorization,
+                account_address: <StarkNetClient as OnChainRegistry>::AccountAddress,
+                signed_data: <StarkNetClient as OnChainRegistry>::SignedData,
+                nonce: Nonce,
+            ) -> Result<(RegistrationOutcome<<StarkNetClient as OnChainRegistry>::TransactionHash>, IdentityProfile), RegistrationError>;
+
+            async fn unregister_contributor(
+                &self,
+                authorization: Authorization,
                 account_address: <StarkNetClient as OnChainRegistry>::AccountAddress,
                 signed_data: <StarkNetClient as OnChainRegistry>::SignedData,
+                nonce: Nonce,
             ) -> Result<<StarkNetClient as OnChainRegistry>::TransactionHash, RegistrationError>;
+
+            async fn migrate_contributor(
+                &self,
+                authorization: Authorization,
+                old_account_address: <StarkNetClient as OnChainRegistry>::AccountAddress,
+                new_account_address: <StarkNetClient as OnChainRegistry>::AccountAddress,
+                new_signed_data: <StarkNetClient as OnChainRegistry>::SignedData,
+                old_signed_data: Option<<StarkNetClient as OnChainRegistry>::SignedData>,
+                nonce: Nonce,
+            ) -> Result<<StarkNetClient as OnChainRegistry>::TransactionHash, RegistrationError>;
+
+            async fn get_registration_status(
+                &self,
+                transaction_hash: <StarkNetClient as OnChainRegistry>::TransactionHash,
+            ) -> Result<TransactionStatus, RegistrationError>;
         }
     }
 
@@ -64,7 +168,8 @@ mod tests {
         let registerer_mock = MockMyRegisterer::new();
 
         let router = rest::router::new(
-            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>
+            Box::new(registerer_mock) as Box<dyn Registerer<GitHubClient, StarkNetClient>>,
+            Box::new(InMemoryIdempotencyStore::new(Duration::from_secs(60))),
         );
 
         let client = Client::tracked(router).expect("valid rocket instance");
@@ -77,7 +182,7 @@ mod tests {
         );
         assert_eq!(
             response.headers().get_one("Access-Control-Allow-Methods"),
-            Some("POST, PUT, GET, PATCH, OPTIONS")
+            Some("POST, PUT, GET, PATCH, DELETE, OPTIONS")
         );
         assert_eq!(
             response.headers().get_one("Access-Control-Allow-Headers"),